@@ -2,7 +2,7 @@ use std::{
     collections::VecDeque,
     io::{stdin, Read},
     num::NonZeroU32,
-    panic::catch_unwind,
+    panic::{catch_unwind, AssertUnwindSafe},
     time::Instant,
 };
 
@@ -64,7 +64,7 @@ fn main() {
                     "Found {} simplificaiton candiates in {} ns. Total types:{}",
                     simplify_candidates.len(),
                     start.elapsed().as_nanos(),
-                    asm.class_defs().len()
+                    asm.class_defs().count()
                 );
             }
             "deadcode" => asm.eliminate_dead_code(),
@@ -85,7 +85,12 @@ fn main() {
                     );
                 }
             }
-            "gc" => asm = asm.clone().link(Assembly::default()),
+            "gc" => {
+                asm = asm
+                    .clone()
+                    .link(Assembly::default())
+                    .expect("ERROR: the assembly contains an alias cycle")
+            }
             "exit" => return,
             "open" => {
                 let path = body;
@@ -100,11 +105,17 @@ fn main() {
                 let loaded_asm = postcard::from_bytes(&asm_bytes).unwrap();
                 // Loading an assembly
 
-                if asm.class_defs().is_empty() {
+                if asm.class_defs().next().is_none() {
                     asm = loaded_asm;
                 } else {
                     println!("Linking an assembly");
-                    asm = asm.clone().link(loaded_asm);
+                    match asm.clone().link(loaded_asm) {
+                        Ok(linked) => asm = linked,
+                        Err(cycle) => {
+                            eprintln!("Could not link the assembly: {cycle}");
+                            continue;
+                        }
+                    }
                 }
                 println!("Loaded assembly");
             }
@@ -289,7 +300,7 @@ fn find_invalid_c(asm: &Assembly) {
     {
         let mut copy = asm.clone();
         misolate(&mut copy, *key);
-        if !is_valid_c(&copy, fail_id) {
+        if !is_valid_c(&mut copy, fail_id) {
             fail_id += 1;
             eprintln!("Invalid c code methodid {key:?} fail_id:{fail_id}")
         } else {
@@ -298,10 +309,13 @@ fn find_invalid_c(asm: &Assembly) {
     }
     println!("Found {} faliures, saved to tmp", fail_id)
 }
-fn is_valid_c(asm: &Assembly, id: u32) -> bool {
+fn is_valid_c(asm: &mut Assembly, id: u32) -> bool {
     #[cfg(not(miri))]
     {
-        catch_unwind(|| asm.export(format!("/tmp/test{id}.out"), CExporter::new(false))).is_ok()
+        catch_unwind(AssertUnwindSafe(|| {
+            asm.export(format!("/tmp/test{id}.out"), CExporter::new(false))
+        }))
+        .is_ok()
     }
     #[cfg(miri)]
     {
@@ -342,7 +356,10 @@ fn misolate(asm: &mut Assembly, isolate_id: MethodRefIdx) {
     asm.new_method(entrypoint);
     asm.eliminate_dead_code();
     // GC
-    *asm = asm.clone().link(Assembly::default());
+    *asm = asm
+        .clone()
+        .link(Assembly::default())
+        .expect("ERROR: the assembly contains an alias cycle");
     asm.remove_dead_statics();
 }
 fn parse_id(id: &str, asm: &Assembly) -> MethodRefIdx {
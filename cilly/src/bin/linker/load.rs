@@ -42,7 +42,9 @@ fn load_ar(
                 .expect("ERROR: Could not load the assembly file!");
             let assembly = postcard::from_bytes(&asm_bytes)
                 .unwrap_or_else(|_| panic!("ERROR:Could not decode the assembly file {name}!"));
-            final_assembly = final_assembly.link(assembly);
+            final_assembly = final_assembly
+                .link(assembly)
+                .unwrap_or_else(|cycle| panic!("ERROR: Could not link {name}: {cycle}"));
         } else if ext.contains("o") {
             let mut file_bytes = Vec::with_capacity(0x100);
             entry
@@ -72,13 +74,17 @@ pub fn load_assemblies(
         let asm: cilly::v2::Assembly =
             postcard::from_bytes(&asm_bytes).expect("ERROR:Could not decode the assembly file!");
 
-        final_assembly = final_assembly.link(asm);
+        final_assembly = final_assembly
+            .link(asm)
+            .unwrap_or_else(|cycle| panic!("ERROR: Could not link {asm_path}: {cycle}"));
     }
     for asm_path in archives {
         let mut asm_file =
             std::fs::File::open(asm_path).expect("ERROR: Could not open the assembly file!");
         let (asm, linkable) = load_ar(&mut asm_file).expect("Could not open archive");
-        final_assembly = final_assembly.link(asm);
+        final_assembly = final_assembly
+            .link(asm)
+            .unwrap_or_else(|cycle| panic!("ERROR: Could not link {asm_path}: {cycle}"));
         linkables.extend(linkable);
     }
     println!("Loaded assmeblies");
@@ -491,6 +491,9 @@ fn main() {
 
     final_assembly.patch_missing_methods(&externs, &modifies_errno, &overrides);
     final_assembly.patch_missing_methods(&externs, &modifies_errno, &overrides);
+    if *INLINE_SIMD_HELPERS {
+        final_assembly.inline_simd_helpers();
+    }
 
     add_mandatory_statics(&mut final_assembly);
 
@@ -605,6 +608,12 @@ config!(ABORT_ON_ERROR, bool, false);
 config!(C_MODE, bool, false);
 config!(JAVA_MODE, bool, false);
 config!(PANIC_MANAGED_BT, bool, false);
+config!(
+    INLINE_SIMD_HELPERS,
+    bool,
+    false,
+    "Tells the linker to inline the SIMD helper methods into their call sites."
+);
 /*
 lazy_static! {
     #[doc = "Tells the linker to not remove any dead code."]pub static ref KEEP_DEAD_CODE:bool = {
@@ -675,6 +675,23 @@ pub const LIBM_FNS: &[&str] = &[
     "tgamma",
     "tgammaf",
     "fmodl",
+    "sqrtf128",
+    "fmaf128",
+    "fabsf128",
+    "floorf128",
+    "ceilf128",
+    "truncf128",
+    "powf128",
+    "expf128",
+    "exp2f128",
+    "logf128",
+    "log2f128",
+    "log10f128",
+    "sinf128",
+    "cosf128",
+    "copysignf128",
+    "fminf128",
+    "fmaxf128",
 ];
 pub const F128_SYMBOLS: &[&str] = &[
     "__addtf3", "__subtf3", "__multf3", "__divtf3", "__eqtf2", "__netf2", "__getf2", "__lttf2",
@@ -2,10 +2,11 @@ use super::{
     bimap::{BiMap, BiMapIndex, IntoBiMapIndex},
     cilnode::{BinOp, ExtendKind, MethodKind, PtrCastRes, UnOp},
     opt::{OptFuel, SideEffectInfoCache},
-    Access, CILNode, CILRoot, ClassDef, ClassDefIdx, ClassRef, ClassRefIdx, Const, Exporter,
-    FieldDesc, FieldIdx, FnSig, Int, IntoAsmIndex, MethodDef, MethodDefIdx, MethodRef,
-    MethodRefIdx, NodeIdx, RootIdx, SigIdx, StaticFieldDesc, StaticFieldIdx, StringIdx, Type,
-    TypeIdx,
+    typecheck::{typecheck_calli_args, TypeCheckError},
+    Access, CILIter, CILIterElem, CILNode, CILRoot, ClassDef, ClassDefIdx, ClassRef, ClassRefIdx,
+    Const, Exporter, FieldDesc, FieldIdx, Float, FnSig, Int, IntoAsmIndex, MethodDef, MethodDefIdx,
+    MethodRef, MethodRefIdx, NodeIdx, RootIdx, SigIdx, StaticFieldDesc, StaticFieldIdx, StringIdx,
+    Type, TypeIdx,
 };
 use crate::{asm::Assembly as V1Asm, utilis::encode, v2::MethodImpl};
 use crate::{config, IString};
@@ -122,11 +123,45 @@ impl Assembly {
             tmp_method.typecheck(self);
         }
     }
-    #[must_use]
-    pub fn class_defs(&self) -> &FxHashMap<ClassDefIdx, ClassDef> {
-        &self.class_defs
+    /// Checks that the arguments of each indirect call in this assembly match the signature it is made with.
+    /// Errors in the arguments themselves are left to [`Self::typecheck`].
+    /// # Errors
+    /// Returns an error describing the first indirect call with the wrong number or types of arguments.
+    pub fn typecheck_callis(&mut self) -> Result<(), TypeCheckError> {
+        let mut callis = vec![];
+        for (_, method) in self.method_defs() {
+            let MethodImpl::MethodBody { blocks, locals } = method.resolved_implementation(self)
+            else {
+                continue;
+            };
+            let method_callis: Vec<_> = blocks
+                .iter()
+                .flat_map(super::BasicBlock::iter_roots)
+                .flat_map(|root| CILIter::new(self[root].clone(), self))
+                .filter_map(|elem| match elem {
+                    CILIterElem::Node(CILNode::CallI(calli))
+                    | CILIterElem::Root(CILRoot::CallI(calli)) => Some((calli.1, calli.2)),
+                    _ => None,
+                })
+                .collect();
+            if !method_callis.is_empty() {
+                callis.push((method.sig(), locals.clone(), method_callis));
+            }
+        }
+        for (sig, locals, method_callis) in callis {
+            for (fn_sig, args) in method_callis {
+                let called_sig = self[fn_sig].clone();
+                if let Err(
+                    err @ (TypeCheckError::IndirectCallArgcWrong { .. }
+                    | TypeCheckError::IndirectCallArgTypeWrong { .. }),
+                ) = typecheck_calli_args(&called_sig, &args, sig, &locals, self)
+                {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
     }
-
     #[must_use]
     pub fn method_ref_to_def(&self, method: MethodRefIdx) -> Option<MethodDefIdx> {
         if self.method_defs.contains_key(&MethodDefIdx(method)) {
@@ -184,6 +219,52 @@ impl Assembly {
             }
         }
     }
+    /// Inlines calls to the SIMD helpers(the `simd_*` methods of the main module) into their call sites. Those helpers are usually
+    /// nothing more than a call to a .NET vector operation, so inlining them removes the overhead of an additional call.
+    ///
+    /// Only helpers with a single-root body are inlined, and only when all the arguments of the call are simple(arguments, locals or constants),
+    /// so that duplicating them can't change the behaviour of the program. Should be called after all the helpers are generated. Returns the number of
+    /// inlined calls.
+    pub fn inline_simd_helpers(&mut self) -> usize {
+        let main_module = *self.main_module();
+        let mut fuel = OptFuel::new(u32::MAX);
+        let mut inlined = 0;
+        let method_def_idxs: Box<[_]> = self.method_defs.keys().copied().collect();
+        for method in method_def_idxs {
+            self.rebuild_method(method, &mut |node, asm| {
+                let CILNode::Call(info) = &node else {
+                    return node;
+                };
+                let (mref, args) = info.as_ref();
+                let is_helper = asm[*mref].class() == main_module
+                    && asm[asm[*mref].name()].starts_with("simd_");
+                let args_simple = args.iter().all(|arg| {
+                    matches!(
+                        asm[*arg],
+                        CILNode::LdArg(_) | CILNode::LdLoc(_) | CILNode::Const(_)
+                    )
+                });
+                if !is_helper || !args_simple {
+                    return node;
+                }
+                let Some(def) = asm
+                    .method_def_from_ref(*mref)
+                    .filter(|def| matches!(def.kind(), MethodKind::Static))
+                    .cloned()
+                else {
+                    return node;
+                };
+                match super::opt::trivial_inline_node(&def, args, &mut fuel, asm) {
+                    Some(inlined_node) => {
+                        inlined += 1;
+                        inlined_node
+                    }
+                    None => node,
+                }
+            });
+        }
+        inlined
+    }
     /// Finds all methods matching the closure
     pub fn methods_with<'a>(
         &'a self,
@@ -203,6 +284,42 @@ impl Assembly {
         modify(self, &mut borrowed);
         self.return_methoddef(def_id, borrowed);
     }
+    /// Rewrites the body of the method `def_id`, by applying `node_map` to each of its nodes(children first), and
+    /// re-interning the results. Useful for passes(like const-folding) which need to replace nodes.
+    pub fn rebuild_method(
+        &mut self,
+        def_id: MethodDefIdx,
+        node_map: &mut impl FnMut(CILNode, &mut Self) -> CILNode,
+    ) {
+        self.modify_methodef(
+            |asm, def| def.map_roots(asm, &mut |root, _| root, node_map),
+            def_id,
+        );
+    }
+    /// Follows a chain of [`MethodImpl::AliasFor`], returning the method reference the chain ends at.
+    /// If `mref` is not an alias, it is returned unchanged.
+    /// # Errors
+    /// Returns an [`AliasCycle`] naming the methods involved if the aliases form a cycle.
+    pub fn resolve_alias(&self, mref: MethodRefIdx) -> Result<MethodRefIdx, AliasCycle> {
+        let mut chain = vec![mref];
+        let mut current = mref;
+        while let Some(MethodImpl::AliasFor(target)) = self
+            .method_def_from_ref(current)
+            .map(MethodDef::implementation)
+        {
+            if chain.contains(target) {
+                chain.push(*target);
+                let methods = chain
+                    .iter()
+                    .map(|mref| self[self[*mref].name()].into())
+                    .collect();
+                return Err(AliasCycle { methods });
+            }
+            chain.push(*target);
+            current = *target;
+        }
+        Ok(current)
+    }
     pub fn find_methods_matching<'a, P: std::str::pattern::Pattern + Clone + 'a>(
         &self,
         pat: P,
@@ -389,6 +506,33 @@ impl Assembly {
 
         idx
     }
+    /// Returns a static field of the main module holding the 128 bit constant `bytes`. The field(and the code initializing it)
+    /// are only created once per constant. Used to get a pointer to small constant values, like SIMD vectors.
+    pub fn const_value_static(&mut self, bytes: u128) -> StaticFieldDesc {
+        let main_module = self.main_module();
+        let name: IString = format!("a_{bytes:x}").into();
+        let name_idx = self.alloc_string(name.clone());
+        let field_desc = StaticFieldDesc::new(*main_module, name_idx, Type::Int(Int::U128));
+        if self
+            .class_mut(main_module)
+            .has_static_field(name_idx, field_desc.tpe())
+        {
+            return field_desc;
+        }
+        let field = self.add_static(Type::Int(Int::U128), name, false, main_module);
+        let val = self.alloc_node(Const::U128(bytes));
+        let set = self.alloc_root(CILRoot::SetStaticField { field, val });
+        self.add_cctor(&[set]);
+        field_desc
+    }
+    /// Returns the class representing the floating-point type `float`(eg. `System.Single` for `f32`).
+    pub fn float_class(&mut self, float: Float) -> ClassRefIdx {
+        float.class(self)
+    }
+    /// Returns the class containing the math functions for the floating-point type `float`(eg. `System.MathF` for `f32`).
+    pub fn math_class(&mut self, float: Float) -> ClassRefIdx {
+        float.math_class(self)
+    }
     /// Adds a new class definition to this type
     pub fn class_def(&mut self, def: ClassDef) -> ClassDefIdx {
         let cref = def.ref_to();
@@ -586,6 +730,21 @@ impl Assembly {
     pub fn get_prealllocated_methodref(&self, mref: MethodRef) -> Option<MethodRefIdx> {
         self.method_refs.1.get(&mref).copied()
     }
+    /// Searches the interned method references for one with the given class, name and signature.
+    /// Unlike [`Self::get_prealllocated_methodref`], this ignores the method kind and generics: if several references
+    /// differ only in those, the one allocated first is returned. This is a linear search over all method references.
+    #[must_use]
+    pub fn find_method_ref(
+        &self,
+        class: ClassRefIdx,
+        name: StringIdx,
+        sig: SigIdx,
+    ) -> Option<MethodRefIdx> {
+        self.method_refs
+            .iter()
+            .find(|(_, mref)| mref.class() == class && mref.name() == name && mref.sig() == sig)
+            .map(|(idx, _)| idx)
+    }
     /// Returns a reference to the static initializer
     pub fn cctor(&mut self) -> MethodDefIdx {
         let mref = self.cctor_mref();
@@ -711,11 +870,23 @@ impl Assembly {
         });
     }
     #[cfg(not(miri))]
-    pub fn export(&self, out: impl AsRef<std::path::Path>, exporter: impl Exporter) {
+    pub fn export(&mut self, out: impl AsRef<std::path::Path>, exporter: impl Exporter) {
+        // Indirect calls with mismatched arguments only fail at runtime, so debug builds check them before exporting.
+        let checked = if cfg!(debug_assertions) {
+            self.typecheck_callis()
+                .map_err(|err| format!("Indirect call does not match its signature: {err:?}"))
+        } else {
+            Ok(())
+        };
+        let res = checked.and_then(|()| {
+            exporter
+                .export(self, out.as_ref())
+                .map_err(|err| format!("{err:?}"))
+        });
         if *LINKER_RECOVER {
-            eprintln!("{:?}", exporter.export(self, out.as_ref()));
+            eprintln!("{res:?}");
         } else {
-            exporter.export(self, out.as_ref()).unwrap();
+            res.unwrap();
         }
     }
     pub fn memory_info(&self) {
@@ -739,6 +910,25 @@ impl Assembly {
         }
     }
 
+    /// Returns the number of interned nodes, roots, signatures and strings, the number of defined methods and classes, and
+    /// the largest method(by block count).
+    #[must_use]
+    pub fn stats(&self) -> AssemblyStats {
+        let largest_method = self
+            .method_defs
+            .iter()
+            .filter_map(|(idx, def)| Some((*idx, def.implementation().blocks()?.len())))
+            .max_by_key(|(_, blocks)| *blocks);
+        AssemblyStats {
+            nodes: self.nodes.len(),
+            roots: self.roots.len(),
+            methods: self.method_defs.len(),
+            classes: self.class_defs.len(),
+            sigs: self.sigs.len(),
+            strings: self.strings.len(),
+            largest_method,
+        }
+    }
     pub(crate) fn iter_class_defs(&self) -> impl Iterator<Item = &ClassDef> {
         self.class_defs.values()
     }
@@ -826,12 +1016,11 @@ impl Assembly {
     pub(crate) fn eliminate_dead_types(&mut self) {
         let mut previosly_ressurected: FxHashSet<ClassDefIdx> = self
             .method_defs()
-            .values()
-            .flat_map(|method| method.iter_types(self))
+            .flat_map(|(_, method)| method.iter_types(self))
             .flat_map(|tpe| tpe.iter_class_refs(self).collect::<Vec<_>>())
             .filter_map(|cref| self.class_ref_to_def(cref))
             .collect();
-        previosly_ressurected.extend(self.class_defs().iter().filter_map(|(defid, def)| {
+        previosly_ressurected.extend(self.class_defs().filter_map(|(defid, def)| {
             if def.access().is_extern() {
                 Some(defid)
             } else {
@@ -1007,8 +1196,10 @@ impl Assembly {
         }
     }
 
-    #[must_use]
-    pub fn link(mut self, other: Self) -> Self {
+    /// Links `other` into this assembly, merging their class definitions.
+    /// # Errors
+    /// Returns an [`AliasCycle`] if the linked assembly contains a chain of [`MethodImpl::AliasFor`] which loops back on itself.
+    pub fn link(mut self, other: Self) -> Result<Self, AliasCycle> {
         let original_str = self.alloc_string(MAIN_MODULE);
         for def in other.iter_class_defs() {
             let translated = self.translate_class_def(&other, def);
@@ -1023,11 +1214,29 @@ impl Assembly {
             }
         }
         assert_eq!(self.alloc_string(MAIN_MODULE), original_str);
-        self
+        self.check_alias_cycles()?;
+        Ok(self)
+    }
+    /// Checks that no chain of [`MethodImpl::AliasFor`] loops back on itself.
+    /// Linking can introduce such a cycle, even if each of the linked assemblies was valid on its own.
+    /// # Errors
+    /// Returns the first [`AliasCycle`] found, naming the methods involved.
+    pub fn check_alias_cycles(&self) -> Result<(), AliasCycle> {
+        for (mref, _) in
+            self.methods_with(|_, _, def| matches!(def.implementation(), MethodImpl::AliasFor(_)))
+        {
+            self.resolve_alias(**mref)?;
+        }
+        Ok(())
     }
 
-    pub(crate) fn method_defs(&self) -> &FxHashMap<MethodDefIdx, MethodDef> {
-        &self.method_defs
+    /// Iterates over all method definitions in this assembly, in no particular order.
+    pub fn method_defs(&self) -> impl Iterator<Item = (MethodDefIdx, &MethodDef)> {
+        self.method_defs.iter().map(|(idx, def)| (*idx, def))
+    }
+    /// Iterates over all class definitions in this assembly, in no particular order.
+    pub fn class_defs(&self) -> impl Iterator<Item = (ClassDefIdx, &ClassDef)> {
+        self.class_defs.iter().map(|(idx, def)| (*idx, def))
     }
 
     /// Checks if this assembly contains a reference [`ClassRef`]
@@ -1264,7 +1473,7 @@ impl Assembly {
 
     fn link_gc(self) -> Self {
         let mut clone = self.clone();
-        clone = clone.link(self);
+        clone = clone.link(self).unwrap_or_else(|cycle| panic!("{cycle}"));
         clone
     }
 }
@@ -1296,6 +1505,58 @@ pub fn encoded_stats<T: Serialize + for<'a> Deserialize<'a>>(val: &T) -> (&'stat
     (type_name::<T>(), buff.len())
 }
 
+/// A chain of [`MethodImpl::AliasFor`] which loops back on itself, returned by [`Assembly::resolve_alias`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasCycle {
+    /// Names of the methods in the cycle, in alias order. The first method is repeated at the end.
+    methods: Vec<IString>,
+}
+impl AliasCycle {
+    /// Names of the methods forming the cycle, in alias order.
+    #[must_use]
+    pub fn methods(&self) -> &[IString] {
+        &self.methods
+    }
+}
+impl std::fmt::Display for AliasCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Method alias cycle detected: ")?;
+        for (idx, name) in self.methods.iter().enumerate() {
+            if idx != 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{name}")?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for AliasCycle {}
+/// Size statistics of an [`Assembly`], returned by [`Assembly::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssemblyStats {
+    pub nodes: usize,
+    pub roots: usize,
+    pub methods: usize,
+    pub classes: usize,
+    pub sigs: usize,
+    pub strings: usize,
+    /// The method with the most basic blocks, and its block count.
+    pub largest_method: Option<(MethodDefIdx, usize)>,
+}
+impl std::fmt::Display for AssemblyStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nodes:{} roots:{} methods:{} classes:{} sigs:{} strings:{}",
+            self.nodes, self.roots, self.methods, self.classes, self.sigs, self.strings
+        )?;
+        if let Some((_, blocks)) = self.largest_method {
+            write!(f, " largest method blocks:{blocks}")?;
+        }
+        Ok(())
+    }
+}
+
 pub static ILASM_FLAVOUR: std::sync::LazyLock<IlasmFlavour> = std::sync::LazyLock::new(|| {
     if String::from_utf8_lossy(
             &std::process::Command::new(ilasm_path()).arg("--help")
@@ -1421,6 +1682,54 @@ fn add_user_init() {
     asm.add_user_init(&roots);
 }
 #[test]
+fn inline_simd_add() {
+    use super::{tpe::simd::SIMDVector, BasicBlock};
+    let mut asm = Assembly::default();
+    let mut patcher = MissingMethodPatcher::default();
+    super::builtins::simd::simd(&mut asm, &mut patcher);
+    let i32x4 = Type::SIMDVector(SIMDVector::new(Int::I32.into(), 4));
+    let sig = asm.sig([i32x4, i32x4], i32x4);
+    let main_module = asm.main_module();
+    let simd_add = asm.alloc_string("simd_add");
+    let simd_add = asm.alloc_methodref(MethodRef::new(
+        *main_module,
+        simd_add,
+        sig,
+        MethodKind::Static,
+        vec![].into(),
+    ));
+    // caller(a, b) = simd_add(a, b)
+    let lhs = asm.alloc_node(CILNode::LdArg(0));
+    let rhs = asm.alloc_node(CILNode::LdArg(1));
+    let call = asm.alloc_node(CILNode::Call(Box::new((simd_add, [lhs, rhs].into()))));
+    let ret = asm.alloc_root(CILRoot::Ret(call));
+    let name = asm.alloc_string("caller");
+    let caller = asm.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![None, None],
+    ));
+    asm.patch_missing_methods(&FxHashMap::default(), &FxHashSet::default(), &patcher);
+    assert_eq!(asm.inline_simd_helpers(), 1);
+    // The call to the helper got replaced by the .NET vector addition, which still adds the arguments.
+    let root = asm.method_defs[&caller].blocks(&asm).unwrap()[0].roots()[0];
+    let CILRoot::Ret(res) = asm[root] else {
+        panic!("Expected a return");
+    };
+    let CILNode::Call(info) = &asm[res] else {
+        panic!("Expected a call to a .NET vector operation");
+    };
+    assert_eq!(&asm[asm[info.0].name()], "Add");
+    assert_eq!(info.1.as_ref(), &[lhs, rhs]);
+}
+#[test]
 fn export() {
     use super::il_exporter::*;
 
@@ -1577,10 +1886,319 @@ fn link() {
         asm.add_user_init(&[uinit]);
         asm
     };
-    let mut asm = asm1.link(asm2);
+    let mut asm = asm1.link(asm2).unwrap();
     asm.eliminate_dead_code();
     asm.realloc_roots();
     #[cfg(not(miri))]
     asm.export("/tmp/link_test.exe", ILExporter::new(*ILASM_FLAVOUR, false));
 }
+#[test]
+fn calli_argc_mismatch() {
+    use super::BasicBlock;
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let called_sig = asm.sig([Type::Int(Int::I32), Type::Int(Int::I32)], Type::Void);
+    let fn_ptr = asm.alloc_node(CILNode::LdArg(0));
+    let arg = asm.alloc_node(super::Const::I32(0));
+    // Only one argument passed to a function expecting 2.
+    let calli = asm.alloc_root(CILRoot::CallI(Box::new((fn_ptr, called_sig, [arg].into()))));
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let sig = asm.sig([Type::FnPtr(called_sig)], Type::Void);
+    let name = asm.alloc_string("bad_calli");
+    asm.new_method(MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![calli, ret], 0, None)],
+            locals: vec![],
+        },
+        vec![None],
+    ));
+    assert!(matches!(
+        asm.typecheck_callis(),
+        Err(TypeCheckError::IndirectCallArgcWrong {
+            expected: 2,
+            got: 1
+        })
+    ));
+}
+#[test]
+fn stats() {
+    use super::BasicBlock;
+    let mut asm = Assembly::default();
+    let empty = asm.stats();
+    assert_eq!(empty.methods, 0);
+    assert_eq!(empty.nodes, 0);
+    assert_eq!(empty.strings, 0);
+    assert_eq!(empty.largest_method, None);
+    let main_module = asm.main_module();
+    let sig = asm.sig([], Type::Void);
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let small = asm.alloc_string("small");
+    let small = asm.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        small,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    ));
+    let big = asm.alloc_string("big");
+    let big = asm.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        big,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![
+                BasicBlock::new(vec![ret], 0, None),
+                BasicBlock::new(vec![ret], 1, None),
+            ],
+            locals: vec![],
+        },
+        vec![],
+    ));
+    let stats = asm.stats();
+    assert_ne!(small, big);
+    assert_eq!(stats.methods, 2);
+    assert_eq!(stats.classes, 1);
+    assert_eq!(stats.roots, 1);
+    // Both methods only return, so there are no nodes, and they share their signature.
+    assert_eq!(stats.nodes, 0);
+    assert_eq!(stats.sigs, 1);
+    // The name of the main module, "small" and "big".
+    assert_eq!(stats.strings, 3);
+    assert_eq!(stats.largest_method, Some((big, 2)));
+}
+#[test]
+fn find_method_ref() {
+    let mut asm = Assembly::default();
+    let main_module = *asm.main_module();
+    let sig = asm.sig([Type::Int(Int::I32)], Type::Void);
+    let other_sig = asm.sig([], Type::Void);
+    let name = asm.alloc_string("do_something");
+    let other_name = asm.alloc_string("do_something_else");
+    assert_eq!(asm.find_method_ref(main_module, name, sig), None);
+    let mref = asm.alloc_methodref(MethodRef::new(
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        [].into(),
+    ));
+    asm.alloc_methodref(MethodRef::new(
+        main_module,
+        other_name,
+        sig,
+        MethodKind::Static,
+        [].into(),
+    ));
+    assert_eq!(asm.find_method_ref(main_module, name, sig), Some(mref));
+    assert_eq!(asm.find_method_ref(main_module, name, other_sig), None);
+    // The kind is ignored, so the first matching reference is still found.
+    asm.alloc_methodref(MethodRef::new(
+        main_module,
+        name,
+        sig,
+        MethodKind::Virtual,
+        [].into(),
+    ));
+    assert_eq!(asm.find_method_ref(main_module, name, sig), Some(mref));
+}
+#[test]
+fn method_defs() {
+    let mut asm = Assembly::default();
+    assert_eq!(asm.method_defs().count(), 0);
+    let main_module = asm.main_module();
+    let sig = asm.sig([], Type::Void);
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let mut defs: Vec<_> = ["a", "b"]
+        .into_iter()
+        .map(|name| {
+            let name = asm.alloc_string(name);
+            asm.new_method(MethodDef::new(
+                Access::Public,
+                main_module,
+                name,
+                sig,
+                MethodKind::Static,
+                MethodImpl::MethodBody {
+                    blocks: vec![super::BasicBlock::new(vec![ret], 0, None)],
+                    locals: vec![],
+                },
+                vec![],
+            ))
+        })
+        .collect();
+    let found: Vec<_> = asm.method_defs().map(|(idx, _)| idx).collect();
+    assert_eq!(found.len(), 2);
+    defs.retain(|idx| !found.contains(idx));
+    assert!(defs.is_empty());
+    assert!(asm
+        .method_defs()
+        .all(|(idx, def)| asm[idx].name() == def.name()));
+    let classes: Vec<_> = asm.class_defs().map(|(idx, _)| idx).collect();
+    assert_eq!(classes, vec![main_module]);
+}
+#[test]
+fn rebuild_method() {
+    use super::BasicBlock;
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let sig = asm.sig([], Type::Int(Int::I32));
+    let name = asm.alloc_string("add_consts");
+    // (1 + 2) + 3
+    let sum = asm.biop(Const::I32(1), Const::I32(2), BinOp::Add);
+    let sum = asm.biop(sum, Const::I32(3), BinOp::Add);
+    let sum = asm.alloc_node(sum);
+    let ret = asm.alloc_root(CILRoot::Ret(sum));
+    let def = asm.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    ));
+    asm.rebuild_method(def, &mut |node, asm| match node {
+        CILNode::BinOp(lhs, rhs, BinOp::Add) => match (&asm[lhs], &asm[rhs]) {
+            (CILNode::Const(lhs), CILNode::Const(rhs)) => match (lhs.as_ref(), rhs.as_ref()) {
+                (Const::I32(lhs), Const::I32(rhs)) => Const::I32(lhs.wrapping_add(*rhs)).into(),
+                _ => node,
+            },
+            _ => node,
+        },
+        _ => node,
+    });
+    let folded = asm.alloc_node(Const::I32(6));
+    let roots = asm[def].blocks(&asm).unwrap()[0].roots().to_vec();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(asm[roots[0]], CILRoot::Ret(folded));
+}
+#[cfg(test)]
+fn alias_method(asm: &mut Assembly, name: &str, mimpl: MethodImpl) -> MethodDefIdx {
+    let main_module = asm.main_module();
+    let sig = asm.sig([], Type::Void);
+    let name = asm.alloc_string(name);
+    asm.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        mimpl,
+        vec![],
+    ))
+}
+#[test]
+fn resolve_alias() {
+    use super::BasicBlock;
+    let mut asm = Assembly::default();
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let base = alias_method(
+        &mut asm,
+        "base",
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+    );
+    let middle = alias_method(&mut asm, "middle", MethodImpl::AliasFor(*base));
+    let top = alias_method(&mut asm, "top", MethodImpl::AliasFor(*middle));
+    assert_eq!(asm.resolve_alias(*base), Ok(*base));
+    assert_eq!(asm.resolve_alias(*middle), Ok(*base));
+    assert_eq!(asm.resolve_alias(*top), Ok(*base));
+    assert!(asm[top].resolved_implementation(&asm).blocks().is_some());
+}
+#[test]
+fn resolve_alias_cycle() {
+    let mut asm = Assembly::default();
+    let main_module = *asm.main_module();
+    let sig = asm.sig([], Type::Void);
+    let b_name = asm.alloc_string("b");
+    let b_ref = asm.alloc_methodref(MethodRef::new(
+        main_module,
+        b_name,
+        sig,
+        MethodKind::Static,
+        [].into(),
+    ));
+    let a = alias_method(&mut asm, "a", MethodImpl::AliasFor(b_ref));
+    let b = alias_method(&mut asm, "b", MethodImpl::AliasFor(*a));
+    let cycle = asm.resolve_alias(*a).unwrap_err();
+    assert_eq!(
+        cycle.to_string(),
+        "Method alias cycle detected: a -> b -> a"
+    );
+    let cycle = asm[b].try_resolved_implementation(&asm).unwrap_err();
+    assert_eq!(&*cycle.methods()[0], "a");
+}
+#[test]
+fn link_alias_cycle() {
+    // Each assembly is valid on its own, but `a` aliases `b` and `b` aliases `a` once they are linked.
+    fn aliased(from: &str, to: &str) -> Assembly {
+        let mut asm = Assembly::default();
+        let main_module = *asm.main_module();
+        let sig = asm.sig([], Type::Void);
+        let to = asm.alloc_string(to);
+        let to = asm.alloc_methodref(MethodRef::new(
+            main_module,
+            to,
+            sig,
+            MethodKind::Static,
+            [].into(),
+        ));
+        alias_method(&mut asm, from, MethodImpl::AliasFor(to));
+        asm
+    }
+    let Err(cycle) = aliased("a", "b").link(aliased("b", "a")) else {
+        panic!("linking should have detected the alias cycle");
+    };
+    assert_eq!(&*cycle.methods()[0], &*cycle.methods()[2]);
+    assert!(cycle.to_string().starts_with("Method alias cycle detected"));
+}
+#[test]
+fn const_value_static() {
+    let mut asm = Assembly::default();
+    let a = asm.const_value_static(0xDEAD_BEEF);
+    let roots = asm.stats().roots;
+    // The same constant is only stored once.
+    assert_eq!(asm.const_value_static(0xDEAD_BEEF), a);
+    assert_eq!(asm.stats().roots, roots);
+    let b = asm.const_value_static(u128::MAX);
+    assert_ne!(a, b);
+    assert_eq!(b.tpe(), Type::Int(Int::U128));
+}
+#[test]
+fn const_dedup() {
+    use super::hashable::{HashableF32, HashableF64};
+    let mut asm = Assembly::default();
+    // Structurally equal consts are interned to a single node.
+    let a = asm.alloc_node(Const::I32(5));
+    let b = asm.alloc_node(Const::I32(5));
+    assert_eq!(a, b);
+    // Consts of different types are distinct, even if their values are the same.
+    assert_ne!(a, asm.alloc_node(Const::U32(5)));
+    // Floats are compared bitwise, so NaNs dedupe, while 0.0 and -0.0 do not.
+    assert_eq!(
+        asm.alloc_node(Const::F32(HashableF32(f32::NAN))),
+        asm.alloc_node(Const::F32(HashableF32(f32::NAN)))
+    );
+    assert_ne!(
+        asm.alloc_node(Const::F64(HashableF64(0.0))),
+        asm.alloc_node(Const::F64(HashableF64(-0.0)))
+    );
+}
 config! {LINKER_RECOVER,bool,false}
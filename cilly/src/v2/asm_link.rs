@@ -1,7 +1,7 @@
 use super::{
     asm::{CCTOR, TCCTOR, USER_INIT},
     Assembly, BasicBlock, CILNode, CILRoot, ClassDef, ClassDefIdx, ClassRef, ClassRefIdx,
-    FieldDesc, FnSig, MethodDef, MethodDefIdx, MethodRef, StaticFieldDesc, Type,
+    FieldDesc, FnSig, MethodDef, MethodRef, StaticFieldDesc, Type,
 };
 impl Assembly {
     pub(crate) fn translate_type(&mut self, source: &Self, tpe: Type) -> Type {
@@ -22,6 +22,7 @@ impl Assembly {
             | Type::Void
             | Type::PlatformObject
             | Type::PlatformGeneric(_, _)
+            // SIMD vectors store their element type and lane count inline, so they need no translation.
             | Type::SIMDVector(_) => tpe,
             Type::ClassRef(class_ref) => {
                 Type::ClassRef(self.translate_class_ref(source, class_ref))
@@ -106,6 +107,9 @@ impl Assembly {
                 super::Const::PlatformString(pstr) => CILNode::Const(Box::new(
                     super::Const::PlatformString(self.alloc_string(source[*pstr].as_ref())),
                 )),
+                super::Const::Null(class_ref) => CILNode::Const(Box::new(super::Const::Null(
+                    self.translate_class_ref(source, *class_ref),
+                ))),
                 _ => node.clone(),
             },
             CILNode::BinOp(a, b, op) => {
@@ -627,7 +631,7 @@ impl Assembly {
             let mut method_definition = self.translate_method_def(source, source.method_def(*mdef));
             let method_ref = self.alloc_methodref(method_definition.ref_to());
             // 1st Take the orignal method, if it exists(we need this to be able to mutate methods)
-            let original = self.method_defs().get(&MethodDefIdx(method_ref));
+            let original = self.method_def_from_ref(method_ref);
             let method_definition = match original {
                 Some(original) => {
                     assert_eq!(method_definition.name(), original.name());
@@ -660,3 +664,83 @@ impl Assembly {
     }
 }
 const SPECIAL_METHOD_NAMES: &[&str] = &[CCTOR, TCCTOR, USER_INIT];
+#[test]
+fn translate_ld_null() {
+    let mut source = Assembly::default();
+    // Allocate an unrelated class ref first, so the class ref indices differ between the assemblies.
+    let _ = ClassRef::gc_handle(&mut source);
+    let exception = ClassRef::exception(&mut source);
+    let mut target = Assembly::default();
+    let translated = target.translate_node(&source, super::Const::Null(exception).into());
+    let CILNode::Const(cst) = translated else {
+        panic!("LdNull translated to {translated:?}");
+    };
+    let super::Const::Null(class_ref) = *cst else {
+        panic!("LdNull translated to {cst:?}");
+    };
+    assert_eq!(class_ref, ClassRef::exception(&mut target));
+}
+#[test]
+fn translate_simd_vector() {
+    use super::{tpe::simd::SIMDVector, Float};
+    let mut source = Assembly::default();
+    // Allocate an unrelated type first, so the type indices differ between the assemblies.
+    let _ = source.nptr(Type::Bool);
+    let f32x4 = Type::SIMDVector(SIMDVector::new(Float::F32.into(), 4));
+    let ptr = source.nptr(f32x4);
+    let mut target = Assembly::default();
+    for tpe in [f32x4, ptr] {
+        let translated = target.translate_type(&source, tpe);
+        let translated = match translated {
+            Type::Ptr(inner) => target[inner],
+            _ => translated,
+        };
+        let vec = translated
+            .as_simdvector()
+            .unwrap_or_else(|| panic!("{tpe:?} translated to {translated:?}"));
+        assert_eq!(vec.elem(), Float::F32.into());
+        assert_eq!(vec.count(), 4);
+    }
+}
+#[test]
+fn link_ld_null() {
+    use super::{cilnode::MethodKind, Access, Const, MethodImpl};
+    let mut source = Assembly::default();
+    let exception = ClassRef::exception(&mut source);
+    let main_module = source.main_module();
+    let name = source.alloc_string("null_exception");
+    let sig = source.sig([], Type::ClassRef(exception));
+    let null = source.alloc_node(Const::Null(exception));
+    let ret = source.alloc_root(CILRoot::Ret(null));
+    source.new_method(MethodDef::new(
+        Access::Public,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    ));
+    let mut target = Assembly::default();
+    // Allocate an unrelated class ref first, so the class ref indices differ between the assemblies.
+    let _ = ClassRef::gc_handle(&mut target);
+    let mut linked = target.link(source).unwrap();
+    let (_, def) = linked
+        .method_defs()
+        .find(|(_, def)| &linked[def.name()] == "null_exception")
+        .expect("null_exception was lost while linking");
+    let blocks = def.blocks(&linked).expect("null_exception has no body");
+    let CILRoot::Ret(node) = linked[blocks[0].roots()[0]] else {
+        panic!("Ret translated to {:?}", linked[blocks[0].roots()[0]]);
+    };
+    let CILNode::Const(cst) = linked[node].clone() else {
+        panic!("LdNull translated to {:?}", linked[node]);
+    };
+    let Const::Null(class_ref) = *cst else {
+        panic!("LdNull translated to {cst:?}");
+    };
+    assert_eq!(class_ref, ClassRef::exception(&mut linked));
+}
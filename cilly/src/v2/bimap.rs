@@ -62,6 +62,13 @@ impl<Key: IntoBiMapIndex + Eq + Hash + Clone + Debug, Value: Eq + Hash + Clone +
     pub fn contais_val(&self, def: Value) -> bool {
         self.1.contains_key(&def)
     }
+    /// Iterates over all allocated values and their keys, in allocation order.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &Value)> {
+        self.0
+            .iter()
+            .zip(1..)
+            .map(|(val, key)| (Key::from_index(NonZeroU32::new(key).unwrap()), val))
+    }
     pub fn iter_keys(&self) -> impl Iterator<Item = Key> {
         (1..(self.0.len() as u32)).map(|key| Key::from_index(NonZeroU32::new(key).unwrap()))
     }
@@ -71,9 +71,9 @@ pub fn compare_exchange(
     value: NodeIdx,
     comaprand: NodeIdx,
 ) -> NodeIdx {
-    match int.size().unwrap_or(8) {
+    match int.size() {
         // u16 is buggy :(. TODO: fix it.
-        1 | 2 => {
+        Some(1 | 2) => {
             let compare_exchange = asm.alloc_string("atomic_cmpxchng8_i32");
 
             let i32 = Type::Int(int);
@@ -113,7 +113,8 @@ pub fn compare_exchange(
                 extend: crate::cilnode::ExtendKind::ZeroExtend,
             })
         }
-        4..=8 => {
+        // `usize` and `isize` use the `nuint`/`nint` overloads, so their width always matches the pointer size of the target.
+        Some(4 | 8) | None => {
             let compare_exchange = asm.alloc_string("CompareExchange");
 
             let tpe = Type::Int(int);
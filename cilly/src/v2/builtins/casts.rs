@@ -1,6 +1,11 @@
-use crate::v2::{
-    asm::MissingMethodPatcher, cilnode::ExtendKind, Assembly, BasicBlock, CILNode, CILRoot, Float,
-    Int, MethodImpl,
+use crate::{
+    cil_root::CILRoot as V1Root,
+    v2::{
+        asm::MissingMethodPatcher,
+        cilnode::ExtendKind,
+        cilroot::{BranchCond, CmpKind},
+        Assembly, BasicBlock, CILNode, CILRoot, Float, Int, MethodImpl,
+    },
 };
 fn clampy_float_to_int(
     asm: &mut Assembly,
@@ -111,6 +116,63 @@ fn float_to_int(asm: &mut Assembly, int: Int, float: Float, patcher: &mut Missin
     };
     patcher.insert(name, Box::new(generator));
 }
+fn extend_kind(int: Int) -> ExtendKind {
+    if int.is_signed() {
+        ExtendKind::SignExtend
+    } else {
+        ExtendKind::ZeroExtend
+    }
+}
+/// Body of `checked_cast_{src}_{target}`, which performs an `as` cast, and throws if the value of the integer changed.
+fn checked_int_cast_impl(src: Int, target: Int, asm: &mut Assembly) -> MethodImpl {
+    let ld_arg_0 = asm.alloc_node(CILNode::LdArg(0));
+    let cast = asm.int_cast(ld_arg_0, target, extend_kind(src));
+    let cast = asm.alloc_node(cast);
+    // If the value does not survive a round trip, some bits were lost.
+    let round_trip = asm.int_cast(cast, src, extend_kind(target));
+    let round_trip = asm.alloc_node(round_trip);
+    let mut roots = vec![asm.alloc_root(CILRoot::Branch(Box::new((
+        1,
+        0,
+        Some(BranchCond::Ne(round_trip, ld_arg_0)),
+    ))))];
+    // The round trip does not catch sign changes, like `-1_i32 as u32`.
+    let sign_check = match (src.is_signed(), target.is_signed()) {
+        (true, false) => Some((ld_arg_0, src)),
+        (false, true) => Some((cast, target)),
+        _ => None,
+    };
+    if let Some((val, int)) = sign_check {
+        let zero = asm.alloc_node(int.zero());
+        roots.push(asm.alloc_root(CILRoot::Branch(Box::new((
+            1,
+            0,
+            Some(BranchCond::Lt(val, zero, CmpKind::Signed)),
+        )))));
+    }
+    roots.push(asm.alloc_root(CILRoot::Ret(cast)));
+    let msg = format!(
+        "Checked cast: a {src} can't be cast to a {target} without losing information.",
+        src = src.name(),
+        target = target.name()
+    );
+    let throw = CILRoot::from_v1(&V1Root::throw(&msg, asm), asm);
+    let throw = asm.alloc_root(throw);
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(roots, 0, None),
+            BasicBlock::new(vec![throw], 1, None),
+        ],
+        locals: vec![],
+    }
+}
+/// Inserts `checked_cast_{src}_{target}`, used instead of integer `as` casts when cast checks are enabled.
+fn checked_int_cast(asm: &mut Assembly, src: Int, target: Int, patcher: &mut MissingMethodPatcher) {
+    let name = format!("checked_cast_{}_{}", src.name(), target.name());
+    let name = asm.alloc_string(name);
+    let generator = move |_, asm: &mut Assembly| checked_int_cast_impl(src, target, asm);
+    patcher.insert(name, Box::new(generator));
+}
 pub fn insert_casts(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let floats = [Float::F32, Float::F64];
     let ints = [
@@ -133,4 +195,48 @@ pub fn insert_casts(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
             clampy_float_to_int(asm, int, float, patcher);
         }
     }
+    let ints = [
+        Int::U8,
+        Int::I8,
+        Int::U16,
+        Int::I16,
+        Int::U32,
+        Int::I32,
+        Int::U64,
+        Int::I64,
+        Int::USize,
+        Int::ISize,
+    ];
+    for src in ints {
+        for target in ints {
+            if src != target {
+                checked_int_cast(asm, src, target, patcher);
+            }
+        }
+    }
+}
+#[test]
+fn checked_casts_typecheck() {
+    let mut asm = Assembly::default();
+    for (src, target) in [
+        (Int::I64, Int::I32),
+        (Int::I32, Int::U32),
+        (Int::U32, Int::I64),
+        (Int::USize, Int::U8),
+    ] {
+        let MethodImpl::MethodBody { blocks, locals } =
+            checked_int_cast_impl(src, target, &mut asm)
+        else {
+            panic!()
+        };
+        let sig = asm.sig([crate::Type::Int(src)], crate::Type::Int(target));
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use std::num::{NonZeroU32, NonZeroU8};
 
-use crate::{IntoAsmIndex, StaticFieldDesc};
+use crate::{config, IntoAsmIndex, StaticFieldDesc};
 
 use super::{
     asm::MissingMethodPatcher,
@@ -24,31 +24,87 @@ pub mod simd;
 
 pub fn insert_swap_at_generic(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let name = asm.alloc_string("swap_at_generic");
-    let generator = move |_, asm: &mut Assembly| {
-        let buf1 = asm.alloc_node(CILNode::LdArg(0));
-        let buf2 = asm.alloc_node(CILNode::LdArg(1));
-        let size = asm.alloc_node(CILNode::LdArg(2));
-        let tmp_alloc = asm.alloc_node(CILNode::LocAlloc { size });
-        let tmp = asm.alloc_node(CILNode::LdLoc(0));
-        // Alloc the tmp buffer
-        let alloc_buff = asm.alloc_root(CILRoot::StLoc(0, tmp_alloc));
-        // Swap buffers
-        let buf1_to_tmp = asm.alloc_root(CILRoot::CpBlk(Box::new((tmp, buf1, size))));
-        let buf2_to_buff1 = asm.alloc_root(CILRoot::CpBlk(Box::new((buf1, buf2, size))));
-        let tmp_to_buf2 = asm.alloc_root(CILRoot::CpBlk(Box::new((buf2, tmp, size))));
-        // Ret
-        let ret = asm.alloc_root(CILRoot::VoidRet);
-        let uint8_ptr = asm.nptr(Type::Int(Int::U8));
-        MethodImpl::MethodBody {
+    let generator = move |_, asm: &mut Assembly| swap_at_generic(asm, *LOCALLOC_CAP);
+    patcher.insert(name, Box::new(generator));
+}
+/// Generates the body of `swap_at_generic`. The temporary buffer lives on the stack, unless it is bigger than `cap` bytes
+/// (and `cap` is not 0): then, it is allocated on the heap, and freed before returning.
+fn swap_at_generic(asm: &mut Assembly, cap: u64) -> MethodImpl {
+    let buf1 = asm.alloc_node(CILNode::LdArg(0));
+    let buf2 = asm.alloc_node(CILNode::LdArg(1));
+    let size = asm.alloc_node(CILNode::LdArg(2));
+    let tmp = asm.alloc_node(CILNode::LdLoc(0));
+    // Swap buffers
+    let buf1_to_tmp = asm.alloc_root(CILRoot::CpBlk(Box::new((tmp, buf1, size))));
+    let buf2_to_buff1 = asm.alloc_root(CILRoot::CpBlk(Box::new((buf1, buf2, size))));
+    let tmp_to_buf2 = asm.alloc_root(CILRoot::CpBlk(Box::new((buf2, tmp, size))));
+    // Ret
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    // Alloc the tmp buffer
+    let tmp_alloc = asm.alloc_node(CILNode::LocAlloc { size });
+    let alloc_buff = asm.alloc_root(CILRoot::StLoc(0, tmp_alloc));
+    let uint8_ptr = asm.nptr(Type::Int(Int::U8));
+    let locals = vec![(Some(asm.alloc_string("tmp")), asm.alloc_type(uint8_ptr))];
+    if cap == 0 {
+        return MethodImpl::MethodBody {
             blocks: vec![BasicBlock::new(
                 vec![alloc_buff, buf1_to_tmp, buf2_to_buff1, tmp_to_buf2, ret],
                 0,
                 None,
             )],
-            locals: vec![(Some(asm.alloc_string("tmp")), asm.alloc_type(uint8_ptr))],
-        }
-    };
-    patcher.insert(name, Box::new(generator));
+            locals,
+        };
+    }
+    // Buffers above the cap could overflow the stack, so they get allocated on the heap.
+    let cap = asm.alloc_node(Const::USize(cap));
+    let check = asm.alloc_root(CILRoot::Branch(Box::new((
+        1,
+        0,
+        Some(BranchCond::Gt(size, cap, super::cilroot::CmpKind::Unsigned)),
+    ))));
+    let void_ptr = asm.nptr(Type::Void);
+    let native_mem = ClassRef::native_mem(asm);
+    let alloc_sig = asm.sig([Type::Int(Int::USize)], void_ptr);
+    let alloc = asm.new_methodref(native_mem, "Alloc", alloc_sig, MethodKind::Static, []);
+    let free_sig = asm.sig([void_ptr], Type::Void);
+    let free = asm.new_methodref(native_mem, "Free", free_sig, MethodKind::Static, []);
+    let heap_alloc = asm.alloc_node(CILNode::Call(Box::new((alloc, Box::new([size])))));
+    let uint8 = asm.alloc_type(Type::Int(Int::U8));
+    let heap_alloc = asm.alloc_node(CILNode::PtrCast(
+        heap_alloc,
+        Box::new(PtrCastRes::Ptr(uint8)),
+    ));
+    let heap_alloc = asm.alloc_root(CILRoot::StLoc(0, heap_alloc));
+    let free = asm.alloc_root(CILRoot::Call(Box::new((free, Box::new([tmp])))));
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(
+                vec![
+                    check,
+                    alloc_buff,
+                    buf1_to_tmp,
+                    buf2_to_buff1,
+                    tmp_to_buf2,
+                    ret,
+                ],
+                0,
+                None,
+            ),
+            BasicBlock::new(
+                vec![
+                    heap_alloc,
+                    buf1_to_tmp,
+                    buf2_to_buff1,
+                    tmp_to_buf2,
+                    free,
+                    ret,
+                ],
+                1,
+                None,
+            ),
+        ],
+        locals,
+    }
 }
 pub fn insert_bounds_check(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let name = asm.alloc_string("bounds_check");
@@ -437,6 +493,8 @@ fn insert_catch_unwind_stub(asm: &mut Assembly, patcher: &mut MissingMethodPatch
     };
     patcher.insert(name, Box::new(generator));
 }
+/// Inserts `catch_unwind`. Each call gets its own protected region, so when nested `catch_unwind`s are called, a panic caught by the inner one returns normally,
+/// and never reaches the handlers of the outer ones.
 fn insert_catch_unwind(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let name = asm.alloc_string("catch_unwind");
     let generator = move |_, asm: &mut Assembly| {
@@ -533,6 +591,8 @@ fn insert_catch_unwind(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     patcher.insert(name, Box::new(generator));
 }
 const ALLOC_CAP: u64 = u32::MAX as u64;
+// Biggest runtime-sized stack buffer(in bytes) builtins will use, before falling back to the heap. 0(the default) means no cap.
+config!(LOCALLOC_CAP, u64, 0);
 pub(crate) const UNMANAGED_THREAD_START: &str = "UnmanagedThreadStart";
 /// THIS BUILTIN MUST ALWAYS BE INLINED!
 pub fn stack_addr(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
@@ -860,3 +920,31 @@ pub fn argc_argv_init(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     };
     patcher.insert(name, Box::new(generator));
 }
+#[test]
+fn swap_at_generic_cap() {
+    let mut asm = Assembly::default();
+    // Without a cap, the buffer is always on the stack.
+    let uncapped = swap_at_generic(&mut asm, 0);
+    assert_eq!(uncapped.blocks().unwrap().len(), 1);
+    let capped = swap_at_generic(&mut asm, 1024);
+    let blocks = capped.blocks().unwrap();
+    assert_eq!(blocks.len(), 2);
+    // Big buffers must never reach `localloc`, and must be freed before returning.
+    let heap_nodes: Vec<_> = blocks[1]
+        .roots()
+        .iter()
+        .flat_map(|root| crate::v2::CILIter::new(asm[*root].clone(), &asm))
+        .collect();
+    assert!(!heap_nodes
+        .iter()
+        .any(|elem| matches!(elem, crate::v2::CILIterElem::Node(CILNode::LocAlloc { .. }))));
+    let called: Vec<_> = heap_nodes
+        .iter()
+        .filter_map(|elem| match elem {
+            crate::v2::CILIterElem::Node(CILNode::Call(call)) => Some(&asm[asm[call.0].name()]),
+            crate::v2::CILIterElem::Root(CILRoot::Call(call)) => Some(&asm[asm[call.0].name()]),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(called, ["Alloc", "Free"]);
+}
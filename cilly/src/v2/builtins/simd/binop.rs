@@ -1,9 +1,191 @@
+use super::{
+    gather::arg_lane_addr, lane_ptr, lane_size, lanes_loop, needs_scalar_operators, scalar_class,
+};
 use crate::{
-    v2::asm::MissingMethodPatcher, Assembly, BasicBlock, CILNode, CILRoot, MethodImpl,
-    MethodRefIdx, Type,
+    cilnode::{MethodKind, PtrCastRes},
+    tpe::simd::SIMDVector,
+    v2::{asm::MissingMethodPatcher, cilroot::BranchCond},
+    Assembly, BasicBlock, BinOp, CILNode, CILRoot, Const, MethodImpl, MethodRef, MethodRefIdx,
+    NodeIdx, Type,
 };
+/// Body of an element-wise binop, using the generic .NET vector operation `op_dotnet`(eg. `Vector128.Add<T>`).
+fn dotnet_binop(vec: SIMDVector, op_dotnet: &str, asm: &mut Assembly) -> MethodImpl {
+    let elem: Type = vec.elem().into();
+    let extension_class = vec.extension_class(asm);
+    let extension_class = asm[extension_class].clone();
+    let op = asm.alloc_string(op_dotnet);
+    // Generic vec
+    let generic_class = vec.class(asm);
+    let mut generic_class = asm[generic_class].clone();
+    generic_class.set_generics(vec![Type::PlatformGeneric(
+        0,
+        crate::tpe::GenericKind::CallGeneric,
+    )]);
+    let generic_class = asm.alloc_class_ref(generic_class);
+    let op = extension_class.static_mref_generic(
+        &[Type::ClassRef(generic_class), Type::ClassRef(generic_class)],
+        Type::ClassRef(generic_class),
+        op,
+        asm,
+        [elem].into(),
+    );
+    let lhs = asm.alloc_node(CILNode::LdArg(0));
+    let rhs = asm.alloc_node(CILNode::LdArg(1));
+    let res = asm.alloc_node(CILNode::Call(Box::new((op, [lhs, rhs].into()))));
+
+    let ret = asm.alloc_root(CILRoot::Ret(res));
+    MethodImpl::MethodBody {
+        blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+        locals: vec![],
+    }
+}
+/// Applies a binop to two scalar lanes of type `elem`. Lanes with no primitive CIL arithmetic(`i128`, `u128` and `f16`) use the .NET operator `op_scalar`
+/// (eg. `op_Addition`) instead.
+fn scalar_binop(
+    lhs: NodeIdx,
+    rhs: NodeIdx,
+    elem: Type,
+    op: BinOp,
+    op_scalar: &str,
+    asm: &mut Assembly,
+) -> NodeIdx {
+    let val = if needs_scalar_operators(elem) {
+        let scalar_class = scalar_class(elem, asm);
+        let sig = asm.sig([elem, elem], elem);
+        let op_scalar = asm.alloc_string(op_scalar);
+        let op_scalar = asm.alloc_methodref(MethodRef::new(
+            scalar_class,
+            op_scalar,
+            sig,
+            MethodKind::Static,
+            vec![].into(),
+        ));
+        CILNode::Call(Box::new((op_scalar, [lhs, rhs].into())))
+    } else {
+        asm.biop(lhs, rhs, op)
+    };
+    asm.alloc_node(val)
+}
+/// Body of an element-wise binop, which applies the scalar operation to each lane in turn. Used for vectors .NET vector operations don't support.
+/// Lanes with no primitive CIL arithmetic(`i128`, `u128` and `f16`) use the .NET operator `op_scalar`(eg. `op_Addition`) instead.
+fn lane_binop(vec: SIMDVector, op: BinOp, op_scalar: &str, asm: &mut Assembly) -> MethodImpl {
+    let elem: Type = vec.elem().into();
+    let elem_idx = asm.alloc_type(elem);
+    let mut roots = vec![];
+    for lane in 0..u64::from(vec.count()) {
+        let [lhs, rhs] = [0, 1].map(|arg| {
+            let addr = arg_lane_addr(arg, lane, lane_size(vec), elem, asm);
+            asm.alloc_node(CILNode::LdInd {
+                addr,
+                tpe: elem_idx,
+                volatile: false,
+            })
+        });
+        let val = scalar_binop(lhs, rhs, elem, op, op_scalar, asm);
+        // res[lane] = lhs[lane] op rhs[lane]
+        let res = asm.alloc_node(CILNode::LdLocA(0));
+        let res = asm.alloc_node(CILNode::RefToPtr(res));
+        let res = asm.alloc_node(CILNode::PtrCast(res, Box::new(PtrCastRes::Ptr(elem_idx))));
+        let offset = asm.alloc_node(Const::USize(lane * lane_size(vec)));
+        let dst = asm.biop(res, offset, BinOp::Add);
+        let dst = asm.alloc_node(dst);
+        roots.push(asm.alloc_root(CILRoot::StInd(Box::new((dst, val, elem, false)))));
+    }
+    let res = asm.alloc_node(CILNode::LdLoc(0));
+    roots.push(asm.alloc_root(CILRoot::Ret(res)));
+    MethodImpl::MethodBody {
+        blocks: vec![BasicBlock::new(roots, 0, None)],
+        locals: vec![(None, asm.alloc_type(Type::SIMDVector(vec)))],
+    }
+}
+/// Body of an element-wise binop on `vec`. When .NET has a hardware vector type for `vec`(eg. `Vector128<float>`), this uses the vector operation `op_dotnet`.
+/// Otherwise, it falls back to a loop over the lanes.
+fn simd_binop_impl(
+    vec: SIMDVector,
+    op_dotnet: &str,
+    op: BinOp,
+    op_scalar: &str,
+    asm: &mut Assembly,
+) -> MethodImpl {
+    if vec.has_dotnet_vector() {
+        dotnet_binop(vec, op_dotnet, asm)
+    } else {
+        lane_binop(vec, op, op_scalar, asm)
+    }
+}
+/// Body of `simd_*_lanes(dst: *mut T, lhs: *const T, rhs: *const T, count: usize)`, which applies a binop to `count` lanes of type `T`, one by one.
+/// Used for vectors with no .NET vector type(eg. with 3 lanes).
+fn ptr_binop(elem: Type, op: BinOp, op_scalar: &str, asm: &mut Assembly) -> MethodImpl {
+    let elem_idx = asm.alloc_type(elem);
+    let lane_roots = |lane, asm: &mut Assembly| {
+        // dst[lane] = lhs[lane] op rhs[lane]
+        let [lhs, rhs] = [1, 2].map(|arg| {
+            let addr = lane_ptr(arg, elem, lane, asm);
+            asm.alloc_node(CILNode::LdInd {
+                addr,
+                tpe: elem_idx,
+                volatile: false,
+            })
+        });
+        let val = scalar_binop(lhs, rhs, elem, op, op_scalar, asm);
+        let dst = lane_ptr(0, elem, lane, asm);
+        vec![asm.alloc_root(CILRoot::StInd(Box::new((dst, val, elem, false))))]
+    };
+    lanes_loop(3, lane_roots, asm)
+}
+/// Body of an element-wise binop on `vec`, used when the function performing it has no target feature guaranteeing hardware support for `vec`(eg. `avx` for a 256 bit vector).
+/// This checks `VectorN.IsHardwareAccelerated`, and only uses the .NET vector operation when it is. Otherwise, it falls back to a loop over the lanes.
+/// The JIT treats `IsHardwareAccelerated` as a constant, so the check itself is free.
+fn guarded_binop(
+    vec: SIMDVector,
+    op_dotnet: &str,
+    op: BinOp,
+    op_scalar: &str,
+    asm: &mut Assembly,
+) -> MethodImpl {
+    if !vec.has_dotnet_vector() {
+        return lane_binop(vec, op, op_scalar, asm);
+    }
+    let MethodImpl::MethodBody {
+        blocks: accelerated,
+        ..
+    } = dotnet_binop(vec, op_dotnet, asm)
+    else {
+        unreachable!()
+    };
+    let MethodImpl::MethodBody {
+        blocks: software,
+        locals,
+    } = lane_binop(vec, op, op_scalar, asm)
+    else {
+        unreachable!()
+    };
+    let extension_class = vec.extension_class(asm);
+    let extension_class = asm[extension_class].clone();
+    let is_accelerated = asm.alloc_string("get_IsHardwareAccelerated");
+    let is_accelerated = extension_class.static_mref(&[], Type::Bool, is_accelerated, asm);
+    let is_accelerated = asm.alloc_node(CILNode::Call(Box::new((is_accelerated, [].into()))));
+    // if IsHardwareAccelerated goto accelerated else goto software
+    let to_accelerated = asm.alloc_root(CILRoot::Branch(Box::new((
+        1,
+        0,
+        Some(BranchCond::True(is_accelerated)),
+    ))));
+    let to_software = asm.alloc_root(CILRoot::Branch(Box::new((2, 0, None))));
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(vec![to_accelerated, to_software], 0, None),
+            BasicBlock::new(accelerated[0].roots().to_vec(), 1, None),
+            BasicBlock::new(software[0].roots().to_vec(), 2, None),
+        ],
+        locals,
+    }
+}
+/// Defines a builtin, which applies a generic, element-wise .NET vector operation. The integer `Add`, `Subtract` and `Multiply` operations wrap on overflow,
+/// just like Rust SIMD arithmetic, so they never saturate or trap. Each builtin also has a `_guarded` variant, which uses [`guarded_binop`],
+/// and a `_lanes` variant, which uses [`ptr_binop`].
 macro_rules! binop {
-    ($op_name:ident,$op_dotnet:literal) => {
+    ($op_name:ident,$op_dotnet:literal,$op:expr,$op_scalar:literal) => {
         pub fn $op_name(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
             let name = asm.alloc_string(stringify!($op_name));
             let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
@@ -13,43 +195,134 @@ macro_rules! binop {
                     let name = stringify!($op_name);
                     todo!("Can't {name} {comparands:?} ", comparands = sig.inputs()[0])
                 };
-                let elem: Type = comparands.elem().into();
-
-                let extension_class = comparands.extension_class(asm);
-                let extension_class = asm[extension_class].clone();
-                let equals = asm.alloc_string($op_dotnet);
-                // Generic vec
-                let generic_class = comparands.class(asm);
-                let mut generic_class = asm[generic_class].clone();
-                generic_class.set_generics(vec![Type::PlatformGeneric(
-                    0,
-                    crate::tpe::GenericKind::CallGeneric,
-                )]);
-                let generic_class = asm.alloc_class_ref(generic_class);
-                let equals = extension_class.static_mref_generic(
-                    &[Type::ClassRef(generic_class), Type::ClassRef(generic_class)],
-                    Type::ClassRef(generic_class),
-                    equals,
-                    asm,
-                    [elem].into(),
-                );
-                let lhs = asm.alloc_node(CILNode::LdArg(0));
-                let rhs = asm.alloc_node(CILNode::LdArg(1));
-                let res = asm.alloc_node(CILNode::Call(Box::new((equals, [lhs, rhs].into()))));
-
-                let ret = asm.alloc_root(CILRoot::Ret(res));
-                MethodImpl::MethodBody {
-                    blocks: vec![BasicBlock::new(vec![ret], 0, None)],
-                    locals: vec![],
-                }
+                simd_binop_impl(*comparands, $op_dotnet, $op, $op_scalar, asm)
+            };
+            patcher.insert(name, Box::new(generator));
+            let name = asm.alloc_string(concat!(stringify!($op_name), "_guarded"));
+            let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+                let sig = asm[asm[mref].sig()].clone();
+                let Some(comparands) = sig.inputs()[0].as_simdvector() else {
+                    let name = stringify!($op_name);
+                    todo!("Can't {name} {comparands:?} ", comparands = sig.inputs()[0])
+                };
+                guarded_binop(*comparands, $op_dotnet, $op, $op_scalar, asm)
+            };
+            patcher.insert(name, Box::new(generator));
+            let name = asm.alloc_string(concat!(stringify!($op_name), "_lanes"));
+            let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+                let sig = asm[asm[mref].sig()].clone();
+                let elem = asm[sig.inputs()[0].pointed_to().unwrap()];
+                ptr_binop(elem, $op, $op_scalar, asm)
             };
             patcher.insert(name, Box::new(generator));
         }
     };
 }
-binop!(simd_or, "BitwiseOr");
-binop!(simd_add, "Add");
-binop!(simd_and, "BitwiseAnd");
-binop!(simd_sub, "Subtract");
-binop!(simd_mul, "Multiply");
-binop!(simd_div, "Divides");
+binop!(simd_or, "BitwiseOr", BinOp::Or, "op_BitwiseOr");
+binop!(simd_add, "Add", BinOp::Add, "op_Addition");
+binop!(simd_and, "BitwiseAnd", BinOp::And, "op_BitwiseAnd");
+binop!(simd_sub, "Subtract", BinOp::Sub, "op_Subtraction");
+binop!(simd_mul, "Multiply", BinOp::Mul, "op_Multiply");
+binop!(simd_div, "Divide", BinOp::Div, "op_Division");
+binop!(simd_xor, "Xor", BinOp::XOr, "op_ExclusiveOr");
+#[test]
+fn binop_paths() {
+    use crate::{Float, Int};
+    let mut asm = Assembly::default();
+    // `f32x4` is a `Vector128<float>`, so it uses the .NET vector operation.
+    let f32x4 = SIMDVector::new(Float::F32.into(), 4);
+    let MethodImpl::MethodBody { blocks, .. } =
+        simd_binop_impl(f32x4, "Add", BinOp::Add, "op_Addition", &mut asm)
+    else {
+        panic!()
+    };
+    let CILRoot::Ret(res) = asm[blocks[0].roots()[0]] else {
+        panic!("Expected a return");
+    };
+    let CILNode::Call(info) = &asm[res] else {
+        panic!("Expected a call to Vector128.Add");
+    };
+    let mref = asm[info.0].clone();
+    assert_eq!(&asm[mref.name()], "Add");
+    assert_eq!(
+        &asm[asm[mref.class()].name()],
+        "System.Runtime.Intrinsics.Vector128"
+    );
+    // .NET vectors can't hold `u128`s, so this adds lane by lane.
+    let u128x2 = SIMDVector::new(Int::U128.into(), 2);
+    let MethodImpl::MethodBody { blocks, locals } =
+        simd_binop_impl(u128x2, "Add", BinOp::Add, "op_Addition", &mut asm)
+    else {
+        panic!()
+    };
+    // A store for each lane, and the return.
+    assert_eq!(blocks[0].roots().len(), 2 + 1);
+    let vec = Type::SIMDVector(u128x2);
+    let sig = asm.sig([vec, vec], vec);
+    for root in blocks[0].roots().to_vec() {
+        asm[root].clone().typecheck(sig, &locals, &mut asm).unwrap();
+    }
+}
+#[test]
+fn guarded_binop_branches() {
+    use crate::Float;
+    let mut asm = Assembly::default();
+    let f32x8 = SIMDVector::new(Float::F32.into(), 8);
+    let MethodImpl::MethodBody { blocks, locals } =
+        guarded_binop(f32x8, "Add", BinOp::Add, "op_Addition", &mut asm)
+    else {
+        panic!()
+    };
+    // The check, the .NET vector operation, and the lane loop.
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[2].roots().len(), 8 + 1);
+    let CILRoot::Branch(info) = &asm[blocks[0].roots()[0]] else {
+        panic!("Expected a branch on IsHardwareAccelerated");
+    };
+    let Some(BranchCond::True(cond)) = info.2 else {
+        panic!("Expected a branch on IsHardwareAccelerated");
+    };
+    let CILNode::Call(info) = &asm[cond] else {
+        panic!("Expected a call to IsHardwareAccelerated");
+    };
+    let mref = asm[info.0].clone();
+    assert_eq!(&asm[mref.name()], "get_IsHardwareAccelerated");
+    assert_eq!(
+        &asm[asm[mref.class()].name()],
+        "System.Runtime.Intrinsics.Vector256"
+    );
+    let vec = Type::SIMDVector(f32x8);
+    let sig = asm.sig([vec, vec], vec);
+    for root in blocks[2].roots().to_vec() {
+        asm[root].clone().typecheck(sig, &locals, &mut asm).unwrap();
+    }
+}
+#[test]
+fn ptr_binop_typecheck() {
+    use crate::{Float, Int};
+    let mut asm = Assembly::default();
+    for elem in [
+        Type::Int(Int::I32),
+        Type::Int(Int::U128),
+        Type::Float(Float::F64),
+    ] {
+        let MethodImpl::MethodBody { blocks, locals } =
+            ptr_binop(elem, BinOp::Add, "op_Addition", &mut asm)
+        else {
+            panic!()
+        };
+        let elem_ptr = asm.nptr(elem);
+        let sig = asm.sig(
+            [elem_ptr, elem_ptr, elem_ptr, Type::Int(Int::USize)],
+            Type::Void,
+        );
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+    }
+}
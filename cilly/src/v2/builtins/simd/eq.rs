@@ -1,9 +1,56 @@
 use crate::{
-    v2::asm::MissingMethodPatcher, Assembly, BasicBlock, CILNode, CILRoot, MethodImpl,
-    MethodRefIdx, Type,
+    cilnode::{MethodKind, UnOp},
+    v2::{asm::MissingMethodPatcher, BranchCond},
+    Assembly, BasicBlock, CILNode, CILRoot, MethodImpl, MethodRef, MethodRefIdx, Type,
 };
 
-use super::dotnet_vec_cast;
+use super::{
+    dotnet_vec_cast, lane_ptr, lanes_loop, needs_scalar_operators, scalar_class, NEXT_LANE,
+};
+/// Body of `simd_eq_lanes(dst: *mut R, lhs: *const T, rhs: *const T, count: usize)`, which compares `count` lanes of type `T`, one by one.
+/// Lanes which are equal get all their bits set in `dst`, and other lanes are cleared. Used for vectors with no .NET vector type(eg. with 3 lanes).
+fn ptr_eq(elem: Type, result: Type, asm: &mut Assembly) -> MethodImpl {
+    let Some(result_int) = result.as_int() else {
+        panic!("simd_eq can't produce a mask of {result:?}")
+    };
+    let elem_idx = asm.alloc_type(elem);
+    let lane_roots = |lane, asm: &mut Assembly| {
+        let [lhs, rhs] = [1, 2].map(|arg| {
+            let addr = lane_ptr(arg, elem, lane, asm);
+            asm.alloc_node(CILNode::LdInd {
+                addr,
+                tpe: elem_idx,
+                volatile: false,
+            })
+        });
+        // Lanes with no primitive CIL comparison(`i128`, `u128` and `f16`) use `op_Equality`.
+        let differ = if needs_scalar_operators(elem) {
+            let scalar_class = scalar_class(elem, asm);
+            let sig = asm.sig([elem, elem], Type::Bool);
+            let op_equality = asm.alloc_string("op_Equality");
+            let op_equality = asm.alloc_methodref(MethodRef::new(
+                scalar_class,
+                op_equality,
+                sig,
+                MethodKind::Static,
+                vec![].into(),
+            ));
+            let eq = asm.alloc_node(CILNode::Call(Box::new((op_equality, [lhs, rhs].into()))));
+            BranchCond::False(eq)
+        } else {
+            BranchCond::Ne(lhs, rhs)
+        };
+        // dst[lane] = if lhs[lane] == rhs[lane] { !0 } else { 0 }
+        let zero = asm.alloc_node(result_int.zero());
+        let dst = lane_ptr(0, result, lane, asm);
+        let clear = asm.alloc_root(CILRoot::StInd(Box::new((dst, zero, result, false))));
+        let skip = asm.alloc_root(CILRoot::Branch(Box::new((NEXT_LANE, 0, Some(differ)))));
+        let all_set = asm.alloc_node(CILNode::UnOp(zero, UnOp::Not));
+        let set = asm.alloc_root(CILRoot::StInd(Box::new((dst, all_set, result, false))));
+        vec![clear, skip, set]
+    };
+    lanes_loop(3, lane_roots, asm)
+}
 pub(super) fn simd_eq(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let name = asm.alloc_string("simd_eq");
     let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
@@ -48,6 +95,14 @@ pub(super) fn simd_eq(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
         }
     };
     patcher.insert(name, Box::new(generator));
+    let name = asm.alloc_string("simd_eq_lanes");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let result = asm[sig.inputs()[0].pointed_to().unwrap()];
+        let elem = asm[sig.inputs()[1].pointed_to().unwrap()];
+        ptr_eq(elem, result, asm)
+    };
+    patcher.insert(name, Box::new(generator));
 }
 pub(super) fn simd_eq_all(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     let name = asm.alloc_string("simd_eq_all");
@@ -135,3 +190,33 @@ pub(super) fn simd_eq_any(asm: &mut Assembly, patcher: &mut MissingMethodPatcher
     };
     patcher.insert(name, Box::new(generator));
 }
+#[test]
+fn ptr_eq_typecheck() {
+    use crate::{Float, Int};
+    let mut asm = Assembly::default();
+    for (elem, result) in [
+        (Type::Int(Int::I32), Type::Int(Int::I32)),
+        (Type::Float(Float::F64), Type::Int(Int::I64)),
+        (Type::Int(Int::U128), Type::Int(Int::I8)),
+    ] {
+        let MethodImpl::MethodBody { blocks, locals } = ptr_eq(elem, result, &mut asm) else {
+            panic!()
+        };
+        // Clearing the lane, skipping equal lanes, setting the lane, and moving on to the next lane.
+        assert_eq!(blocks[2].roots().len(), 3 + 1);
+        let elem_ptr = asm.nptr(elem);
+        let result_ptr = asm.nptr(result);
+        let sig = asm.sig(
+            [result_ptr, elem_ptr, elem_ptr, Type::Int(Int::USize)],
+            Type::Void,
+        );
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use super::lane_size;
+use crate::{
+    cil_root::CILRoot as V1Root,
+    cilnode::PtrCastRes,
+    config,
+    v2::{asm::MissingMethodPatcher, cilroot::BranchCond},
+    Assembly, BasicBlock, BinOp, CILNode, CILRoot, Const, Int, MethodImpl, MethodRefIdx, NodeIdx,
+    Type,
+};
+config!(
+    SIMD_PTR_CHECKS,
+    bool,
+    false,
+    "Should `simd_gather` and `simd_scatter` check that the pointers of active lanes are non-null and aligned?"
+);
+/// Returns the address of lane `lane` of the vector argument `arg`, cast to a pointer to `elem`.
+pub(super) fn arg_lane_addr(arg: u32, lane: u64, lane_size: u64, elem: Type, asm: &mut Assembly) -> NodeIdx {
+    let elem = asm.alloc_type(elem);
+    let addr = asm.alloc_node(CILNode::LdArgA(arg));
+    let addr = asm.alloc_node(CILNode::RefToPtr(addr));
+    let addr = asm.alloc_node(CILNode::PtrCast(addr, Box::new(PtrCastRes::Ptr(elem))));
+    let offset = asm.alloc_node(Const::USize(lane * lane_size));
+    let addr = asm.biop(addr, offset, BinOp::Add);
+    asm.alloc_node(addr)
+}
+/// Body of `simd_gather::<T, U, V>(val: T, ptrs: U, mask: V) -> T` or `simd_scatter::<T, U, V>(val: T, ptrs: U, mask: V)`.
+/// Lanes with a mask of 0 are skipped: they are not accessed, nor checked, and `simd_gather` returns them unchanged.
+///
+/// Each lane `lane` gets 2 blocks: `1 + 2 * lane` checks the mask, and `2 + 2 * lane` accesses the memory. Block `1 + 2 * N` returns,
+/// and blocks `2 + 2 * N + lane` trap when the pointer of `lane` is invalid.
+fn simd_gather_scatter_impl(
+    t_type: Type,
+    u_type: Type,
+    v_type: Type,
+    is_scatter: bool,
+    ptr_checks: bool,
+    asm: &mut Assembly,
+) -> MethodImpl {
+    let op_name = if is_scatter {
+        "simd_scatter"
+    } else {
+        "simd_gather"
+    };
+    let (Some(vals), Some(ptrs), Some(mask)) = (
+        t_type.as_simdvector().copied(),
+        u_type.as_simdvector().copied(),
+        v_type.as_simdvector().copied(),
+    ) else {
+        panic!("{op_name} called with {t_type:?} {u_type:?} {v_type:?}, which are not all vectors.")
+    };
+    assert!(
+        vals.count() == ptrs.count() && vals.count() == mask.count(),
+        "{op_name} called with vectors of different lengths: {t_type:?} {u_type:?} {v_type:?}"
+    );
+    let lanes = u64::from(vals.count());
+    let elem = Type::from(vals.elem());
+    let elem_idx = asm.alloc_type(elem);
+    let ret_block = 1 + 2 * u32::from(vals.count());
+    let mut blocks = Vec::new();
+    // The result starts as a copy of `val`, so masked-off lanes keep their value.
+    let mut start = if is_scatter {
+        vec![]
+    } else {
+        let val = asm.alloc_node(CILNode::LdArg(0));
+        vec![asm.alloc_root(CILRoot::StLoc(0, val))]
+    };
+    start.push(asm.alloc_root(CILRoot::Branch(Box::new((1, 0, None)))));
+    blocks.push(BasicBlock::new(start, 0, None));
+    for lane in 0..lanes {
+        let check_block = 1 + 2 * u32::try_from(lane).unwrap();
+        let access_block = check_block + 1;
+        let next_block = check_block + 2;
+        // Skip masked-off lanes.
+        let mask_addr = arg_lane_addr(2, lane, lane_size(mask), mask.elem().into(), asm);
+        let mask_tpe = asm.alloc_type(Type::from(mask.elem()));
+        let mask_val = asm.alloc_node(CILNode::LdInd {
+            addr: mask_addr,
+            tpe: mask_tpe,
+            volatile: false,
+        });
+        let skip = asm.alloc_root(CILRoot::Branch(Box::new((
+            next_block,
+            0,
+            Some(BranchCond::False(mask_val)),
+        ))));
+        let access = asm.alloc_root(CILRoot::Branch(Box::new((access_block, 0, None))));
+        blocks.push(BasicBlock::new(vec![skip, access], check_block, None));
+        // Get the pointer of this lane.
+        let ptr_addr = arg_lane_addr(1, lane, lane_size(ptrs), Type::Int(Int::USize), asm);
+        let usize_idx = asm.alloc_type(Type::Int(Int::USize));
+        let ptr = asm.alloc_node(CILNode::LdInd {
+            addr: ptr_addr,
+            tpe: usize_idx,
+            volatile: false,
+        });
+        let mut roots = vec![];
+        if ptr_checks {
+            let trap_block = ret_block + 1 + u32::try_from(lane).unwrap();
+            let zero = asm.alloc_node(Const::USize(0));
+            roots.push(asm.alloc_root(CILRoot::Branch(Box::new((
+                trap_block,
+                0,
+                Some(BranchCond::Eq(ptr, zero)),
+            )))));
+            let align_mask = asm.alloc_node(Const::USize(lane_size(vals) - 1));
+            let misalignment = asm.biop(ptr, align_mask, BinOp::And);
+            let misalignment = asm.alloc_node(misalignment);
+            roots.push(asm.alloc_root(CILRoot::Branch(Box::new((
+                trap_block,
+                0,
+                Some(BranchCond::Ne(misalignment, zero)),
+            )))));
+        }
+        let ptr = asm.alloc_node(CILNode::PtrCast(ptr, Box::new(PtrCastRes::Ptr(elem_idx))));
+        if is_scatter {
+            // *ptrs[lane] = val[lane]
+            let src = arg_lane_addr(0, lane, lane_size(vals), elem, asm);
+            let val = asm.alloc_node(CILNode::LdInd {
+                addr: src,
+                tpe: elem_idx,
+                volatile: false,
+            });
+            roots.push(asm.alloc_root(CILRoot::StInd(Box::new((ptr, val, elem, false)))));
+        } else {
+            // res[lane] = *ptrs[lane]
+            let val = asm.alloc_node(CILNode::LdInd {
+                addr: ptr,
+                tpe: elem_idx,
+                volatile: false,
+            });
+            let res = asm.alloc_node(CILNode::LdLocA(0));
+            let res = asm.alloc_node(CILNode::RefToPtr(res));
+            let res = asm.alloc_node(CILNode::PtrCast(res, Box::new(PtrCastRes::Ptr(elem_idx))));
+            let offset = asm.alloc_node(Const::USize(lane * lane_size(vals)));
+            let dst = asm.biop(res, offset, BinOp::Add);
+            let dst = asm.alloc_node(dst);
+            roots.push(asm.alloc_root(CILRoot::StInd(Box::new((dst, val, elem, false)))));
+        }
+        roots.push(asm.alloc_root(CILRoot::Branch(Box::new((next_block, 0, None)))));
+        blocks.push(BasicBlock::new(roots, access_block, None));
+    }
+    let ret = if is_scatter {
+        asm.alloc_root(CILRoot::VoidRet)
+    } else {
+        let res = asm.alloc_node(CILNode::LdLoc(0));
+        asm.alloc_root(CILRoot::Ret(res))
+    };
+    blocks.push(BasicBlock::new(vec![ret], ret_block, None));
+    if ptr_checks {
+        for lane in 0..lanes {
+            let msg = format!("{op_name}: the pointer of lane {lane} is null or misaligned.");
+            let trap = CILRoot::from_v1(&V1Root::throw(&msg, asm), asm);
+            let trap = asm.alloc_root(trap);
+            blocks.push(BasicBlock::new(
+                vec![trap],
+                ret_block + 1 + u32::try_from(lane).unwrap(),
+                None,
+            ));
+        }
+    }
+    let locals = if is_scatter {
+        vec![]
+    } else {
+        vec![(None, asm.alloc_type(t_type))]
+    };
+    MethodImpl::MethodBody { blocks, locals }
+}
+pub fn simd_gather(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_gather");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let inputs = sig.inputs();
+        simd_gather_scatter_impl(
+            inputs[0],
+            inputs[1],
+            inputs[2],
+            false,
+            *SIMD_PTR_CHECKS,
+            asm,
+        )
+    };
+    patcher.insert(name, Box::new(generator));
+}
+pub fn simd_scatter(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_scatter");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let inputs = sig.inputs();
+        simd_gather_scatter_impl(inputs[0], inputs[1], inputs[2], true, *SIMD_PTR_CHECKS, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+}
+#[test]
+fn gather_checks() {
+    let mut asm = Assembly::default();
+    let i32x4 = Type::SIMDVector(super::SIMDVector::new(Int::I32.into(), 4));
+    let usizex4 = Type::SIMDVector(super::SIMDVector::new(Int::USize.into(), 4));
+    for (is_scatter, ptr_checks) in [(false, false), (false, true), (true, true)] {
+        let output = if is_scatter { Type::Void } else { i32x4 };
+        let MethodImpl::MethodBody { blocks, locals } =
+            simd_gather_scatter_impl(i32x4, usizex4, i32x4, is_scatter, ptr_checks, &mut asm)
+        else {
+            panic!()
+        };
+        // Every lane gets a mask check and an access block. Then, there is the return block, and, if pointers are checked, a trap for each lane.
+        let traps = if ptr_checks { 4 } else { 0 };
+        assert_eq!(blocks.len(), 1 + 4 * 2 + 1 + traps);
+        let sig = asm.sig([i32x4, usizex4, i32x4], output);
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+    }
+}
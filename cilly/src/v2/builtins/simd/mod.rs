@@ -1,11 +1,27 @@
 use crate::{
-    tpe::simd::SIMDVector, v2::asm::MissingMethodPatcher, Assembly, BasicBlock, CILNode, CILRoot,
-    MethodImpl, MethodRefIdx, NodeIdx, Type,
+    cilnode::{ExtendKind, PtrCastRes},
+    tpe::simd::{SIMDElem, SIMDVector},
+    v2::{
+        asm::MissingMethodPatcher,
+        cilroot::{BranchCond, CmpKind},
+    },
+    Assembly, BasicBlock, BinOp, CILNode, CILRoot, ClassRefIdx, Const, Float, Int, MethodImpl,
+    MethodRefIdx, NodeIdx, RootIdx, Type,
 };
 mod eq;
 use eq::*;
 mod binop;
 use binop::*;
+mod unop;
+use unop::*;
+mod gather;
+mod reduce;
+mod select;
+pub use gather::SIMD_PTR_CHECKS;
+use gather::*;
+use reduce::*;
+pub use select::validate_select;
+use select::*;
 fn dotnet_vec_cast(
     src: NodeIdx,
     src_type: SIMDVector,
@@ -20,136 +36,318 @@ fn dotnet_vec_cast(
     src
 }
 
-fn simd_ones_compliment(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
-    let name: crate::StringIdx = asm.alloc_string("simd_ones_compliment");
-    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
-        let sig = asm[asm[mref].sig()].clone();
-
-        let Some(vec_type) = sig.inputs()[0].as_simdvector() else {
-            todo!(
-                "Can't calc the ones compliment of {vec_type:?}",
-                vec_type = sig.inputs()[0]
-            )
-        };
-        let elem: Type = vec_type.elem().into();
-        let extension_class = vec_type.extension_class(asm);
-        let extension_class = asm[extension_class].clone();
-        let ones_compliment = asm.alloc_string("OnesComplement");
-        // Generic vec
-        let generic_class = vec_type.class(asm);
-        let mut generic_class = asm[generic_class].clone();
-        generic_class.set_generics(vec![Type::PlatformGeneric(
-            0,
-            crate::tpe::GenericKind::CallGeneric,
-        )]);
-        let generic_class = asm.alloc_class_ref(generic_class);
-        let ones_compliment = extension_class.static_mref_generic(
-            &[Type::ClassRef(generic_class)],
-            Type::ClassRef(generic_class),
-            ones_compliment,
-            asm,
-            [elem].into(),
-        );
-        let val = asm.alloc_node(CILNode::LdArg(0));
-        let res = asm.alloc_node(CILNode::Call(Box::new((ones_compliment, [val].into()))));
-        let ret = asm.alloc_root(CILRoot::Ret(res));
-        MethodImpl::MethodBody {
-            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
-            locals: vec![],
-        }
+/// Checks that the types of a `simd_shuffle::<T, U, V>` are consistent: the index vector `U` and the result vector `V` must have the same length, and
+/// the elements of the shuffled values `T` must be the same as the elements of `V`.
+pub fn validate_shuffle(t_type: Type, u_type: Type, v_type: Type) -> Result<(), String> {
+    let Some(idx) = u_type.as_simdvector() else {
+        return Err(format!("simd_shuffle index {u_type:?} is not a vector."));
     };
-    patcher.insert(name, Box::new(generator));
+    let Some(res) = v_type.as_simdvector() else {
+        return Err(format!("simd_shuffle result {v_type:?} is not a vector."));
+    };
+    if idx.count() != res.count() {
+        return Err(format!(
+            "simd_shuffle index has {idx_len} lanes, but the result has {res_len} lanes.",
+            idx_len = idx.count(),
+            res_len = res.count()
+        ));
+    }
+    // The shuffled values may be scalars, which are treated as vectors with a length of 1.
+    let input_elem = match t_type {
+        Type::SIMDVector(input) => Type::from(input.elem()),
+        Type::Int(_) | Type::Float(_) => t_type,
+        _ => return Err(format!("simd_shuffle input {t_type:?} is not a vector.")),
+    };
+    if input_elem != Type::from(res.elem()) {
+        return Err(format!(
+            "simd_shuffle input elements {input_elem:?} don't match the result elements {res_elem:?}.",
+            res_elem = Type::from(res.elem())
+        ));
+    }
+    Ok(())
 }
-
-fn simd_neg(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
-    let name: crate::StringIdx = asm.alloc_string("simd_neg");
-    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
-        let sig = asm[asm[mref].sig()].clone();
-
-        let Some(vec_type) = sig.inputs()[0].as_simdvector() else {
-            todo!(
-                "Can't calc the ones compliment of {vec_type:?}",
-                vec_type = sig.inputs()[0]
-            )
-        };
-        let elem: Type = vec_type.elem().into();
-        let extension_class = vec_type.extension_class(asm);
-        let extension_class = asm[extension_class].clone();
-        let ones_compliment = asm.alloc_string("Negate");
-        // Generic vec
-        let generic_class = vec_type.class(asm);
-        let mut generic_class = asm[generic_class].clone();
-        generic_class.set_generics(vec![Type::PlatformGeneric(
-            0,
-            crate::tpe::GenericKind::CallGeneric,
-        )]);
-        let generic_class = asm.alloc_class_ref(generic_class);
-        let ones_compliment = extension_class.static_mref_generic(
-            &[Type::ClassRef(generic_class)],
-            Type::ClassRef(generic_class),
-            ones_compliment,
-            asm,
-            [elem].into(),
-        );
-        let val = asm.alloc_node(CILNode::LdArg(0));
-        let res = asm.alloc_node(CILNode::Call(Box::new((ones_compliment, [val].into()))));
-        let ret = asm.alloc_root(CILRoot::Ret(res));
-        MethodImpl::MethodBody {
-            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
-            locals: vec![],
-        }
+/// Checks that every index of a `simd_shuffle::<T, U, V>` selects a lane of one of the two inputs of type `T`, i.e. is in `0..2 * input_len`.
+pub fn validate_shuffle_idxs(t_type: Type, idxs: &[u64]) -> Result<(), String> {
+    // Scalar inputs are treated as vectors with a length of 1.
+    let input_len = t_type
+        .as_simdvector()
+        .map_or(1, |input| u64::from(input.count()));
+    match idxs.iter().enumerate().find(|(_, idx)| **idx >= 2 * input_len) {
+        Some((lane, idx)) => Err(format!(
+            "simd_shuffle index {idx} of lane {lane} is out of bounds: the inputs have {total} lanes in total.",
+            total = 2 * input_len
+        )),
+        None => Ok(()),
+    }
+}
+/// Size of a single lane of `vec`, in bytes.
+fn lane_size(vec: SIMDVector) -> u64 {
+    u64::from(vec.bits()) / 8 / u64::from(vec.count())
+}
+/// The block of a [`lanes_loop`] which moves on to the next lane. The roots of a lane can jump to it, to skip the rest of the lane.
+const NEXT_LANE: u32 = 3;
+/// Body of a `simd_*_lanes` helper, whose vector arguments are pointers to their lanes, and whose argument `count_arg` is the lane count.
+/// `lane_roots(lane, asm)` builds the roots run for each lane, where `lane` is the index of the current lane.
+///
+/// The count is only known at runtime, so this works for any vector, including ones with a non-power-of-two length(like 3), which .NET has no vector types for.
+fn lanes_loop(
+    count_arg: u32,
+    lane_roots: impl FnOnce(NodeIdx, &mut Assembly) -> Vec<RootIdx>,
+    asm: &mut Assembly,
+) -> MethodImpl {
+    let count = asm.alloc_node(CILNode::LdArg(count_arg));
+    let lane = asm.alloc_node(CILNode::LdLoc(0));
+    // lane = 0;
+    let zero = asm.alloc_node(Const::USize(0));
+    let set_lane = asm.alloc_root(CILRoot::StLoc(0, zero));
+    let to_check = asm.alloc_root(CILRoot::Branch(Box::new((1, 0, None))));
+    // while lane < count
+    let to_ret = asm.alloc_root(CILRoot::Branch(Box::new((
+        4,
+        0,
+        Some(BranchCond::Ge(lane, count, CmpKind::Unsigned)),
+    ))));
+    let to_body = asm.alloc_root(CILRoot::Branch(Box::new((2, 0, None))));
+    let mut body = lane_roots(lane, asm);
+    body.push(asm.alloc_root(CILRoot::Branch(Box::new((NEXT_LANE, 0, None)))));
+    // lane += 1
+    let one = asm.alloc_node(Const::USize(1));
+    let next_lane = asm.biop(lane, one, BinOp::Add);
+    let next_lane = asm.alloc_node(next_lane);
+    let inc_lane = asm.alloc_root(CILRoot::StLoc(0, next_lane));
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(vec![set_lane, to_check], 0, None),
+            BasicBlock::new(vec![to_ret, to_body], 1, None),
+            BasicBlock::new(body, 2, None),
+            BasicBlock::new(vec![inc_lane, to_check], NEXT_LANE, None),
+            BasicBlock::new(vec![ret], 4, None),
+        ],
+        locals: vec![(
+            Some(asm.alloc_string("lane")),
+            asm.alloc_type(Type::Int(Int::USize)),
+        )],
+    }
+}
+/// Returns the address of lane `lane` of the vector pointed to by argument `arg`, whose lanes are of type `elem`.
+fn lane_ptr(arg: u32, elem: Type, lane: NodeIdx, asm: &mut Assembly) -> NodeIdx {
+    let elem_idx = asm.alloc_type(elem);
+    let elem_size = asm.size_of(elem);
+    let elem_size = asm.alloc_node(elem_size);
+    let elem_size = asm.int_cast(elem_size, Int::USize, ExtendKind::ZeroExtend);
+    let elem_size = asm.alloc_node(elem_size);
+    let offset = asm.biop(lane, elem_size, BinOp::Mul);
+    let offset = asm.alloc_node(offset);
+    let ptr = asm.alloc_node(CILNode::LdArg(arg));
+    let ptr = asm.alloc_node(CILNode::PtrCast(ptr, Box::new(PtrCastRes::Ptr(elem_idx))));
+    let addr = asm.biop(ptr, offset, BinOp::Add);
+    asm.alloc_node(addr)
+}
+/// The class defining the .NET operators(eg. `op_Addition`) and math functions(eg. `Sqrt`) of the lane type `elem`.
+fn scalar_class(elem: Type, asm: &mut Assembly) -> ClassRefIdx {
+    match elem {
+        Type::Int(int) => int.class(asm),
+        Type::Float(float) => float.class(asm),
+        _ => panic!("{elem:?} is not a valid SIMD lane type"),
+    }
+}
+/// Checks if lanes of type `elem` have no primitive CIL arithmetic, and must use .NET operators(eg. `op_Addition`) instead.
+fn needs_scalar_operators(elem: Type) -> bool {
+    matches!(
+        elem,
+        Type::Int(Int::I128 | Int::U128) | Type::Float(Float::F16)
+    )
+}
+/// Body of `simd_shuffle::<T, U, V>(x: T, y: T, idx: U) -> V`. The inputs are concatenated, so indices `0..N` select the lanes of `x`, and
+/// indices `N..2N` select the lanes of `y`.
+fn simd_shuffle_impl(t_type: Type, u_type: Type, v_type: Type, asm: &mut Assembly) -> MethodImpl {
+    if let Err(err) = validate_shuffle(t_type, u_type, v_type) {
+        panic!("{err}")
+    }
+    let idx_vec = *u_type.as_simdvector().unwrap();
+    let res_vec = *v_type.as_simdvector().unwrap();
+    let elem = asm.alloc_type(Type::from(res_vec.elem()));
+    let idx_elem = Type::from(idx_vec.elem()).as_int().unwrap();
+    let idx_elem_tpe = asm.alloc_type(Type::Int(idx_elem));
+    let input_size = match t_type {
+        Type::SIMDVector(input) => u64::from(input.bits()) / 8,
+        _ => lane_size(res_vec),
     };
-    patcher.insert(name, Box::new(generator));
+    // Copy both inputs into one buffer.
+    let input_size = asm.alloc_node(Const::USize(input_size));
+    let buff_size = asm.biop(input_size, input_size, BinOp::Add);
+    let buff_size = asm.alloc_node(buff_size);
+    let buff_alloc = asm.alloc_node(CILNode::LocAlloc { size: buff_size });
+    let mut roots = vec![asm.alloc_root(CILRoot::StLoc(1, buff_alloc))];
+    let buff = asm.alloc_node(CILNode::LdLoc(1));
+    let y_dst = asm.biop(buff, input_size, BinOp::Add);
+    for (arg, dst) in [(0, buff), (1, asm.alloc_node(y_dst))] {
+        let src = asm.alloc_node(CILNode::LdArgA(arg));
+        let src = asm.alloc_node(CILNode::RefToPtr(src));
+        roots.push(asm.alloc_root(CILRoot::CpBlk(Box::new((dst, src, input_size)))));
+    }
+    let idxs = asm.alloc_node(CILNode::LdArgA(2));
+    let idxs = asm.alloc_node(CILNode::RefToPtr(idxs));
+    let idxs = asm.alloc_node(CILNode::PtrCast(
+        idxs,
+        Box::new(PtrCastRes::Ptr(idx_elem_tpe)),
+    ));
+    let res = asm.alloc_node(CILNode::LdLocA(0));
+    let res = asm.alloc_node(CILNode::RefToPtr(res));
+    let res = asm.alloc_node(CILNode::PtrCast(res, Box::new(PtrCastRes::Ptr(elem))));
+    let elem_size = asm.alloc_node(Const::USize(lane_size(res_vec)));
+    // res[lane] = buff[idx[lane]]
+    for lane in 0..u64::from(res_vec.count()) {
+        let idx_offset = asm.alloc_node(Const::USize(lane * lane_size(idx_vec)));
+        let idx_addr = asm.biop(idxs, idx_offset, BinOp::Add);
+        let idx_addr = asm.alloc_node(idx_addr);
+        let idx = asm.alloc_node(CILNode::LdInd {
+            addr: idx_addr,
+            tpe: idx_elem_tpe,
+            volatile: false,
+        });
+        let idx = asm.int_cast(idx, Int::USize, ExtendKind::ZeroExtend);
+        let src_offset = asm.biop(idx, elem_size, BinOp::Mul);
+        let src = asm.biop(buff, src_offset, BinOp::Add);
+        let src = asm.alloc_node(src);
+        let src = asm.alloc_node(CILNode::PtrCast(src, Box::new(PtrCastRes::Ptr(elem))));
+        let val = asm.alloc_node(CILNode::LdInd {
+            addr: src,
+            tpe: elem,
+            volatile: false,
+        });
+        let dst_offset = asm.alloc_node(Const::USize(lane * lane_size(res_vec)));
+        let dst = asm.biop(res, dst_offset, BinOp::Add);
+        let dst = asm.alloc_node(dst);
+        roots.push(asm.alloc_root(CILRoot::StInd(Box::new((
+            dst,
+            val,
+            Type::from(res_vec.elem()),
+            false,
+        )))));
+    }
+    let ret = asm.alloc_node(CILNode::LdLoc(0));
+    roots.push(asm.alloc_root(CILRoot::Ret(ret)));
+    let u8_ptr = asm.nptr(Type::Int(Int::U8));
+    MethodImpl::MethodBody {
+        blocks: vec![BasicBlock::new(roots, 0, None)],
+        locals: vec![
+            (None, asm.alloc_type(v_type)),
+            (Some(asm.alloc_string("buff")), asm.alloc_type(u8_ptr)),
+        ],
+    }
 }
-fn simd_abs(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
-    let name: crate::StringIdx = asm.alloc_string("simd_abs");
+fn simd_shuffle(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name: crate::StringIdx = asm.alloc_string("simd_shuffle");
     let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
         let sig = asm[asm[mref].sig()].clone();
-
-        let Some(vec_type) = sig.inputs()[0].as_simdvector() else {
-            todo!(
-                "Can't calc simd_abs of {vec_type:?}",
-                vec_type = sig.inputs()[0]
-            )
-        };
-        let elem: Type = vec_type.elem().into();
-        let extension_class = vec_type.extension_class(asm);
-        let extension_class = asm[extension_class].clone();
-        let ones_compliment = asm.alloc_string("Abs");
-        // Generic vec
-        let generic_class = vec_type.class(asm);
-        let mut generic_class = asm[generic_class].clone();
-        generic_class.set_generics(vec![Type::PlatformGeneric(
-            0,
-            crate::tpe::GenericKind::CallGeneric,
-        )]);
-        let generic_class = asm.alloc_class_ref(generic_class);
-        let ones_compliment = extension_class.static_mref_generic(
-            &[Type::ClassRef(generic_class)],
-            Type::ClassRef(generic_class),
-            ones_compliment,
-            asm,
-            [elem].into(),
-        );
-        let val = asm.alloc_node(CILNode::LdArg(0));
-        let res = asm.alloc_node(CILNode::Call(Box::new((ones_compliment, [val].into()))));
-        let ret = asm.alloc_root(CILRoot::Ret(res));
-        MethodImpl::MethodBody {
-            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
-            locals: vec![],
-        }
+        simd_shuffle_impl(sig.inputs()[0], sig.inputs()[2], *sig.output(), asm)
     };
     patcher.insert(name, Box::new(generator));
 }
-#[allow(dead_code)]
-fn simd_shuffle(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
-    let name: crate::StringIdx = asm.alloc_string("simd_shuffle");
-    let generator = move |_mref: MethodRefIdx, _asm: &mut Assembly| {
-        todo!("simd_shuffle not supported yet!");
-        /*MethodImpl::MethodBody {
-            blocks: vec![BasicBlock::new(vec![], 0, None)],
-            locals: vec![],
-        }*/
+/// The element type of the indices `VectorN.Shuffle` takes for a vector of `elem`, if `VectorN.Shuffle` supports `elem`.
+fn dotnet_shuffle_idx(elem: SIMDElem) -> Option<Int> {
+    match elem {
+        SIMDElem::Int(
+            int @ (Int::U8
+            | Int::I8
+            | Int::U16
+            | Int::I16
+            | Int::U32
+            | Int::I32
+            | Int::U64
+            | Int::I64),
+        ) => Some(int),
+        SIMDElem::Float(Float::F32) => Some(Int::I32),
+        SIMDElem::Float(Float::F64) => Some(Int::I64),
+        _ => None,
+    }
+}
+/// Checks if a `simd_shuffle::<T, U, V>` with the constant indices `idxs` can use `VectorN.Shuffle`(eg. `Vector128.Shuffle`). This requires the result to
+/// have the same type as the inputs, which must have a .NET vector type, and all the indices to select lanes of the first input.
+pub fn supports_dotnet_shuffle(t_type: Type, v_type: Type, idxs: &[u64]) -> bool {
+    let Some(vec) = t_type.as_simdvector() else {
+        return false;
+    };
+    t_type == v_type
+        && vec.has_dotnet_vector()
+        && dotnet_shuffle_idx(vec.elem()).is_some()
+        && idxs.len() == usize::from(vec.count())
+        && idxs.iter().all(|idx| *idx < u64::from(vec.count()))
+}
+/// Body of `simd_shuffle_dotnet::<T, U>(x: T, idx: U) -> T`, which shuffles the lanes of `x` using `VectorN.Shuffle`. The indices get converted to the
+/// index type `VectorN.Shuffle` expects for `T`.
+fn simd_shuffle_dotnet_impl(t_type: Type, u_type: Type, asm: &mut Assembly) -> MethodImpl {
+    let vec = *t_type.as_simdvector().unwrap();
+    let idx_vec = *u_type.as_simdvector().unwrap();
+    let dotnet_idx = dotnet_shuffle_idx(vec.elem())
+        .unwrap_or_else(|| panic!("VectorN.Shuffle does not support {t_type:?}"));
+    let dotnet_idx_vec = SIMDVector::new(dotnet_idx.into(), vec.count());
+    let dotnet_idx_tpe = asm.alloc_type(Type::Int(dotnet_idx));
+    let idx_elem = Type::from(idx_vec.elem()).as_int().unwrap();
+    let idx_elem_tpe = asm.alloc_type(Type::Int(idx_elem));
+    let idxs = asm.alloc_node(CILNode::LdArgA(1));
+    let idxs = asm.alloc_node(CILNode::RefToPtr(idxs));
+    let idxs = asm.alloc_node(CILNode::PtrCast(
+        idxs,
+        Box::new(PtrCastRes::Ptr(idx_elem_tpe)),
+    ));
+    let dotnet_idxs = asm.alloc_node(CILNode::LdLocA(0));
+    let dotnet_idxs = asm.alloc_node(CILNode::RefToPtr(dotnet_idxs));
+    let dotnet_idxs = asm.alloc_node(CILNode::PtrCast(
+        dotnet_idxs,
+        Box::new(PtrCastRes::Ptr(dotnet_idx_tpe)),
+    ));
+    let mut roots = vec![];
+    // dotnet_idxs[lane] = idx[lane]
+    for lane in 0..u64::from(vec.count()) {
+        let idx_offset = asm.alloc_node(Const::USize(lane * lane_size(idx_vec)));
+        let idx_addr = asm.biop(idxs, idx_offset, BinOp::Add);
+        let idx_addr = asm.alloc_node(idx_addr);
+        let idx = asm.alloc_node(CILNode::LdInd {
+            addr: idx_addr,
+            tpe: idx_elem_tpe,
+            volatile: false,
+        });
+        let idx = asm.int_cast(idx, dotnet_idx, ExtendKind::ZeroExtend);
+        let idx = asm.alloc_node(idx);
+        let dst_offset = asm.alloc_node(Const::USize(lane * lane_size(dotnet_idx_vec)));
+        let dst = asm.biop(dotnet_idxs, dst_offset, BinOp::Add);
+        let dst = asm.alloc_node(dst);
+        roots.push(asm.alloc_root(CILRoot::StInd(Box::new((
+            dst,
+            idx,
+            Type::Int(dotnet_idx),
+            false,
+        )))));
+    }
+    let extension_class = vec.extension_class(asm);
+    let extension_class = asm[extension_class].clone();
+    let shuffle = asm.alloc_string("Shuffle");
+    let shuffle = extension_class.static_mref(
+        &[t_type, Type::SIMDVector(dotnet_idx_vec)],
+        t_type,
+        shuffle,
+        asm,
+    );
+    let x = asm.alloc_node(CILNode::LdArg(0));
+    let dotnet_idxs = asm.alloc_node(CILNode::LdLoc(0));
+    let res = asm.alloc_node(CILNode::Call(Box::new((shuffle, [x, dotnet_idxs].into()))));
+    roots.push(asm.alloc_root(CILRoot::Ret(res)));
+    MethodImpl::MethodBody {
+        blocks: vec![BasicBlock::new(roots, 0, None)],
+        locals: vec![(
+            Some(asm.alloc_string("idxs")),
+            asm.alloc_type(Type::SIMDVector(dotnet_idx_vec)),
+        )],
+    }
+}
+fn simd_shuffle_dotnet(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_shuffle_dotnet");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        simd_shuffle_dotnet_impl(sig.inputs()[0], sig.inputs()[1], asm)
     };
     patcher.insert(name, Box::new(generator));
 }
@@ -205,6 +403,8 @@ fn simd_allset(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     patcher.insert(name, Box::new(generator));
 }
 
+/// Registers the generators of all SIMD helpers(`simd_add`, `simd_shuffle`, etc.). The intrinsics only reference those helpers, and a body is generated
+/// lazily, by [`Assembly::patch_missing_methods`], for each combination of name and signature that is actually used.
 pub fn simd(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     simd_eq(asm, patcher);
     simd_ones_compliment(asm, patcher);
@@ -220,4 +420,153 @@ pub fn simd(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
     simd_eq_any(asm, patcher);
     simd_mul(asm, patcher);
     simd_div(asm, patcher);
+    simd_xor(asm, patcher);
+    simd_shuffle(asm, patcher);
+    simd_shuffle_dotnet(asm, patcher);
+    simd_sqrt(asm, patcher);
+    simd_ceil(asm, patcher);
+    simd_floor(asm, patcher);
+    simd_gather(asm, patcher);
+    simd_scatter(asm, patcher);
+    simd_reduce_add(asm, patcher);
+    simd_reduce_mul(asm, patcher);
+    simd_reduce_and(asm, patcher);
+    simd_reduce_or(asm, patcher);
+    simd_reduce_xor(asm, patcher);
+    simd_reduce_min(asm, patcher);
+    simd_reduce_max(asm, patcher);
+    simd_reduce_all(asm, patcher);
+    simd_reduce_any(asm, patcher);
+    simd_select(asm, patcher);
+}
+#[test]
+fn shuffle_validation() {
+    use crate::{tpe::simd::SIMDElem, Float, Int};
+    let u32x4 = Type::SIMDVector(SIMDVector::new(SIMDElem::Int(Int::U32), 4));
+    let u32x8 = Type::SIMDVector(SIMDVector::new(SIMDElem::Int(Int::U32), 8));
+    let f32x4 = Type::SIMDVector(SIMDVector::new(SIMDElem::Float(Float::F32), 4));
+    let f32x8 = Type::SIMDVector(SIMDVector::new(SIMDElem::Float(Float::F32), 8));
+    assert_eq!(validate_shuffle(f32x4, u32x8, f32x8), Ok(()));
+    assert_eq!(
+        validate_shuffle(Type::Float(Float::F32), u32x4, f32x4),
+        Ok(())
+    );
+    // The index and result lengths differ.
+    assert!(validate_shuffle(f32x4, u32x4, f32x8).is_err());
+    // The input and result element types differ.
+    assert!(validate_shuffle(u32x4, u32x4, f32x4).is_err());
+    assert!(validate_shuffle(Type::Int(Int::U32), u32x4, f32x4).is_err());
+    // The index is not a vector.
+    assert!(validate_shuffle(f32x4, Type::Int(Int::U32), f32x4).is_err());
+}
+#[test]
+fn shuffle_idxs() {
+    use crate::{tpe::simd::SIMDElem, Float};
+    let f32x4 = Type::SIMDVector(SIMDVector::new(SIMDElem::Float(Float::F32), 4));
+    // Two `f32x4` have 8 lanes, which can be shuffled into a shorter vector.
+    assert_eq!(validate_shuffle_idxs(f32x4, &[7, 0]), Ok(()));
+    assert!(validate_shuffle_idxs(f32x4, &[0, 8]).is_err());
+    // Scalars are vectors with a length of 1.
+    assert_eq!(
+        validate_shuffle_idxs(Type::Float(Float::F32), &[1, 0]),
+        Ok(())
+    );
+    assert!(validate_shuffle_idxs(Type::Float(Float::F32), &[2, 0]).is_err());
+}
+#[test]
+fn dotnet_shuffle() {
+    let mut asm = Assembly::default();
+    let i32x4 = Type::SIMDVector(SIMDVector::new(Int::I32.into(), 4));
+    let u32x4 = Type::SIMDVector(SIMDVector::new(Int::U32.into(), 4));
+    let i32x8 = Type::SIMDVector(SIMDVector::new(Int::I32.into(), 8));
+    let u128x2 = Type::SIMDVector(SIMDVector::new(Int::U128.into(), 2));
+    assert!(supports_dotnet_shuffle(i32x4, i32x4, &[3, 2, 1, 0]));
+    // Selects a lane of the second input.
+    assert!(!supports_dotnet_shuffle(i32x4, i32x4, &[4, 2, 1, 0]));
+    // The result has a different length.
+    assert!(!supports_dotnet_shuffle(
+        i32x4,
+        i32x8,
+        &[0, 1, 2, 3, 0, 1, 2, 3]
+    ));
+    // .NET vectors can't hold `u128`s.
+    assert!(!supports_dotnet_shuffle(u128x2, u128x2, &[1, 0]));
+    let MethodImpl::MethodBody { blocks, locals } =
+        simd_shuffle_dotnet_impl(i32x4, u32x4, &mut asm)
+    else {
+        panic!()
+    };
+    // Converting the 4 indices, and returning the result of `Vector128.Shuffle`.
+    assert_eq!(blocks[0].roots().len(), 4 + 1);
+    let sig = asm.sig([i32x4, u32x4], i32x4);
+    for root in blocks[0].roots().to_vec() {
+        asm[root].clone().typecheck(sig, &locals, &mut asm).unwrap();
+    }
+}
+#[test]
+fn shuffle_interleave() {
+    use crate::{tpe::simd::SIMDElem, MethodDef};
+    let mut asm = Assembly::default();
+    let i32x4 = Type::SIMDVector(SIMDVector::new(SIMDElem::Int(Int::I32), 4));
+    let u32x8 = Type::SIMDVector(SIMDVector::new(SIMDElem::Int(Int::U32), 8));
+    let i32x8 = Type::SIMDVector(SIMDVector::new(SIMDElem::Int(Int::I32), 8));
+    // Interleaves `x` and `y`, with the index vector `[0, 4, 1, 5, 2, 6, 3, 7]`.
+    let shuffle = simd_shuffle_impl(i32x4, u32x8, i32x8, &mut asm);
+    let sig = asm.sig([i32x4, i32x4, u32x8], i32x8);
+    let main_module = asm.main_module();
+    let name = asm.alloc_string("shuffle");
+    let def = MethodDef::new(
+        crate::Access::Public,
+        main_module,
+        name,
+        sig,
+        crate::cilnode::MethodKind::Static,
+        shuffle,
+        vec![None, None, None],
+    );
+    let blocks = def.blocks(&asm).unwrap();
+    // The buffer allocation, copying `x` and `y`, storing the 8 lanes, and the return.
+    assert_eq!(blocks[0].roots().len(), 1 + 2 + 8 + 1);
+    // `y` is copied right after the 16 bytes of `x`, so its lanes have indices 4..8.
+    let CILRoot::CpBlk(copy_y) = &asm[blocks[0].roots()[2]] else {
+        panic!("Expected a copy of y");
+    };
+    let CILNode::BinOp(_, offset, BinOp::Add) = asm[copy_y.0] else {
+        panic!("Expected an offset into the buffer");
+    };
+    assert_eq!(asm[offset], Const::USize(16).into());
+    let locals: Vec<_> = def.iter_locals(&asm).cloned().collect();
+    for root in blocks[0].roots().to_vec() {
+        asm[root].clone().typecheck(sig, &locals, &mut asm).unwrap();
+    }
+}
+#[test]
+fn helpers_generated_lazily() {
+    use crate::{cilnode::MethodKind, MethodRef};
+    use fxhash::{FxHashMap, FxHashSet};
+    let mut asm = Assembly::default();
+    let mut patcher = MissingMethodPatcher::default();
+    simd(&mut asm, &mut patcher);
+    let i32x4 = Type::SIMDVector(SIMDVector::new(Int::I32.into(), 4));
+    let sig = asm.sig([i32x4, i32x4], i32x4);
+    let main_module = asm.main_module();
+    let simd_add = asm.alloc_string("simd_add");
+    let simd_add = asm.alloc_methodref(MethodRef::new(
+        *main_module,
+        simd_add,
+        sig,
+        MethodKind::Static,
+        vec![].into(),
+    ));
+    assert_eq!(asm.method_defs().count(), 0);
+    asm.patch_missing_methods(&FxHashMap::default(), &FxHashSet::default(), &patcher);
+    // Only the referenced helper gets a body.
+    let defs: Vec<_> = asm.method_defs().map(|(_, def)| def.name()).collect();
+    assert_eq!(defs.len(), 1);
+    assert_eq!(&asm[defs[0]], "simd_add");
+    let def = asm.method_def_from_ref(simd_add).unwrap();
+    assert!(matches!(
+        def.implementation(),
+        MethodImpl::MethodBody { .. }
+    ));
 }
@@ -0,0 +1,362 @@
+use crate::{
+    cilnode::{ExtendKind, PtrCastRes},
+    tpe::simd::SIMDVector,
+    v2::{
+        asm::MissingMethodPatcher,
+        cilroot::{BranchCond, CmpKind},
+    },
+    Assembly, BasicBlock, BinOp, CILNode, CILRoot, Const, Int, MethodImpl, MethodRefIdx, Type,
+};
+/// The operation used to combine the lanes of a vector.
+#[derive(Clone, Copy, Debug)]
+enum ReduceOp {
+    Bin(BinOp),
+    Min,
+    Max,
+}
+/// Body of `simd_reduce_*(lanes: *const T, start: usize, count: usize, init: T) -> T`, which combines `init` with lanes `start..count`, in order.
+///
+/// The lanes are accessed trough a pointer, and their count is passed at runtime, so this works for vectors of any length, including ones with a non-power-of-two
+/// length (like 3), which .NET has no vector types for.
+fn simd_reduce_impl(elem: Type, op: ReduceOp, asm: &mut Assembly) -> MethodImpl {
+    let elem_idx = asm.alloc_type(elem);
+    let lanes = asm.alloc_node(CILNode::LdArg(0));
+    let start = asm.alloc_node(CILNode::LdArg(1));
+    let count = asm.alloc_node(CILNode::LdArg(2));
+    let init = asm.alloc_node(CILNode::LdArg(3));
+    let acc = asm.alloc_node(CILNode::LdLoc(0));
+    let lane = asm.alloc_node(CILNode::LdLoc(1));
+    // acc = init; lane = start;
+    let set_acc = asm.alloc_root(CILRoot::StLoc(0, init));
+    let set_lane = asm.alloc_root(CILRoot::StLoc(1, start));
+    let to_check = asm.alloc_root(CILRoot::Branch(Box::new((1, 0, None))));
+    // while lane < count
+    let to_ret = asm.alloc_root(CILRoot::Branch(Box::new((
+        3,
+        0,
+        Some(BranchCond::Ge(lane, count, CmpKind::Unsigned)),
+    ))));
+    let to_body = asm.alloc_root(CILRoot::Branch(Box::new((2, 0, None))));
+    // val = lanes[lane]
+    let elem_size = asm.size_of(elem);
+    let elem_size = asm.alloc_node(elem_size);
+    let elem_size = asm.int_cast(elem_size, Int::USize, ExtendKind::ZeroExtend);
+    let offset = asm.biop(lane, elem_size, BinOp::Mul);
+    let lanes = asm.alloc_node(CILNode::PtrCast(lanes, Box::new(PtrCastRes::Ptr(elem_idx))));
+    let addr = asm.biop(lanes, offset, BinOp::Add);
+    let addr = asm.alloc_node(addr);
+    let val = asm.alloc_node(CILNode::LdInd {
+        addr,
+        tpe: elem_idx,
+        volatile: false,
+    });
+    let to_next = asm.alloc_root(CILRoot::Branch(Box::new((4, 0, None))));
+    let body = match op {
+        ReduceOp::Bin(op) => {
+            let combined = asm.biop(acc, val, op);
+            let combined = asm.alloc_node(combined);
+            vec![asm.alloc_root(CILRoot::StLoc(0, combined)), to_next]
+        }
+        ReduceOp::Min | ReduceOp::Max => {
+            let kind = match elem {
+                Type::Float(_) => CmpKind::Ordered,
+                Type::Int(int) if int.is_signed() => CmpKind::Signed,
+                _ => CmpKind::Unsigned,
+            };
+            // Keep the accumulator, unless the lane is smaller(for min) or bigger(for max).
+            let keep = if matches!(op, ReduceOp::Min) {
+                BranchCond::Ge(val, acc, kind)
+            } else {
+                BranchCond::Le(val, acc, kind)
+            };
+            let keep = asm.alloc_root(CILRoot::Branch(Box::new((4, 0, Some(keep)))));
+            vec![keep, asm.alloc_root(CILRoot::StLoc(0, val)), to_next]
+        }
+    };
+    // lane += 1
+    let one = asm.alloc_node(crate::Const::USize(1));
+    let next_lane = asm.biop(lane, one, BinOp::Add);
+    let next_lane = asm.alloc_node(next_lane);
+    let inc_lane = asm.alloc_root(CILRoot::StLoc(1, next_lane));
+    let ret = asm.alloc_root(CILRoot::Ret(acc));
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(vec![set_acc, set_lane, to_check], 0, None),
+            BasicBlock::new(vec![to_ret, to_body], 1, None),
+            BasicBlock::new(body, 2, None),
+            BasicBlock::new(vec![ret], 3, None),
+            BasicBlock::new(vec![inc_lane, to_check], 4, None),
+        ],
+        locals: vec![
+            (Some(asm.alloc_string("acc")), elem_idx),
+            (
+                Some(asm.alloc_string("lane")),
+                asm.alloc_type(Type::Int(Int::USize)),
+            ),
+        ],
+    }
+}
+macro_rules! reduce {
+    ($op_name:ident,$op:expr) => {
+        pub fn $op_name(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+            let name = asm.alloc_string(stringify!($op_name));
+            let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+                let elem = *asm[asm[mref].sig()].output();
+                simd_reduce_impl(elem, $op, asm)
+            };
+            patcher.insert(name, Box::new(generator));
+        }
+    };
+}
+reduce!(simd_reduce_add, ReduceOp::Bin(BinOp::Add));
+reduce!(simd_reduce_mul, ReduceOp::Bin(BinOp::Mul));
+reduce!(simd_reduce_and, ReduceOp::Bin(BinOp::And));
+reduce!(simd_reduce_or, ReduceOp::Bin(BinOp::Or));
+reduce!(simd_reduce_xor, ReduceOp::Bin(BinOp::XOr));
+reduce!(simd_reduce_min, ReduceOp::Min);
+reduce!(simd_reduce_max, ReduceOp::Max);
+/// Body of `simd_reduce_all(mask: T) -> bool`(when `all` is true) or `simd_reduce_any(mask: T) -> bool`. The lanes of a mask are either all zeros or all ones,
+/// so the most significant bit of each lane is enough to tell them apart. `VectorN.ExtractMostSignificantBits` gathers those bits into an integer(a move mask),
+/// which then only needs to be compared with the all-set or none-set pattern.
+fn simd_reduce_mask_impl(mask: SIMDVector, all: bool, asm: &mut Assembly) -> MethodImpl {
+    let elem: Type = mask.elem().into();
+    let extension_class = mask.extension_class(asm);
+    let extension_class = asm[extension_class].clone();
+    let extract = asm.alloc_string("ExtractMostSignificantBits");
+    // Generic vec
+    let generic_class = mask.class(asm);
+    let mut generic_class = asm[generic_class].clone();
+    generic_class.set_generics(vec![Type::PlatformGeneric(
+        0,
+        crate::tpe::GenericKind::CallGeneric,
+    )]);
+    let generic_class = asm.alloc_class_ref(generic_class);
+    // `Vector512` has up to 64 lanes, so its move mask is an `ulong`.
+    let (bits_tpe, all_set) = if mask.bits() == 512 {
+        (
+            Int::U64,
+            Const::U64(u64::MAX >> (64 - u32::from(mask.count()))),
+        )
+    } else {
+        (
+            Int::U32,
+            Const::U32(u32::MAX >> (32 - u32::from(mask.count()))),
+        )
+    };
+    let extract = extension_class.static_mref_generic(
+        &[Type::ClassRef(generic_class)],
+        Type::Int(bits_tpe),
+        extract,
+        asm,
+        [elem].into(),
+    );
+    let mask = asm.alloc_node(CILNode::LdArg(0));
+    let bits = asm.alloc_node(CILNode::Call(Box::new((extract, [mask].into()))));
+    let res = if all {
+        let all_set = asm.alloc_node(all_set);
+        asm.biop(bits, all_set, BinOp::Eq)
+    } else {
+        let none_set = asm.alloc_node(if bits_tpe == Int::U64 {
+            Const::U64(0)
+        } else {
+            Const::U32(0)
+        });
+        asm.biop(bits, none_set, BinOp::GtUn)
+    };
+    let res = asm.alloc_node(res);
+    let ret = asm.alloc_root(CILRoot::Ret(res));
+    MethodImpl::MethodBody {
+        blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+        locals: vec![],
+    }
+}
+/// Body of `simd_reduce_all_lanes(lanes: *const T, count: usize) -> bool`(when `all` is true) or `simd_reduce_any_lanes`. Used for masks with no .NET vector type
+/// (like `Mask<i32, 3>`), which have no move mask: this checks the lanes one by one, and stops at the first lane deciding the result.
+fn simd_reduce_mask_lanes(elem: Type, all: bool, asm: &mut Assembly) -> MethodImpl {
+    let elem_idx = asm.alloc_type(elem);
+    let lanes = asm.alloc_node(CILNode::LdArg(0));
+    let count = asm.alloc_node(CILNode::LdArg(1));
+    let lane = asm.alloc_node(CILNode::LdLoc(0));
+    // lane = 0;
+    let zero = asm.alloc_node(Const::USize(0));
+    let set_lane = asm.alloc_root(CILRoot::StLoc(0, zero));
+    let to_check = asm.alloc_root(CILRoot::Branch(Box::new((1, 0, None))));
+    // while lane < count
+    let to_done = asm.alloc_root(CILRoot::Branch(Box::new((
+        3,
+        0,
+        Some(BranchCond::Ge(lane, count, CmpKind::Unsigned)),
+    ))));
+    let to_body = asm.alloc_root(CILRoot::Branch(Box::new((2, 0, None))));
+    // val = lanes[lane]
+    let elem_size = asm.size_of(elem);
+    let elem_size = asm.alloc_node(elem_size);
+    let elem_size = asm.int_cast(elem_size, Int::USize, ExtendKind::ZeroExtend);
+    let offset = asm.biop(lane, elem_size, BinOp::Mul);
+    let lanes = asm.alloc_node(CILNode::PtrCast(lanes, Box::new(PtrCastRes::Ptr(elem_idx))));
+    let addr = asm.biop(lanes, offset, BinOp::Add);
+    let addr = asm.alloc_node(addr);
+    let val = asm.alloc_node(CILNode::LdInd {
+        addr,
+        tpe: elem_idx,
+        volatile: false,
+    });
+    // A clear lane decides `all`, and a set lane decides `any`.
+    let decided = if all {
+        BranchCond::False(val)
+    } else {
+        BranchCond::True(val)
+    };
+    let to_decided = asm.alloc_root(CILRoot::Branch(Box::new((4, 0, Some(decided)))));
+    // lane += 1
+    let one = asm.alloc_node(Const::USize(1));
+    let next_lane = asm.biop(lane, one, BinOp::Add);
+    let next_lane = asm.alloc_node(next_lane);
+    let inc_lane = asm.alloc_root(CILRoot::StLoc(0, next_lane));
+    let done = asm.alloc_node(Const::Bool(all));
+    let done = asm.alloc_root(CILRoot::Ret(done));
+    let decided = asm.alloc_node(Const::Bool(!all));
+    let decided = asm.alloc_root(CILRoot::Ret(decided));
+    MethodImpl::MethodBody {
+        blocks: vec![
+            BasicBlock::new(vec![set_lane, to_check], 0, None),
+            BasicBlock::new(vec![to_done, to_body], 1, None),
+            BasicBlock::new(vec![to_decided, inc_lane, to_check], 2, None),
+            BasicBlock::new(vec![done], 3, None),
+            BasicBlock::new(vec![decided], 4, None),
+        ],
+        locals: vec![(
+            Some(asm.alloc_string("lane")),
+            asm.alloc_type(Type::Int(Int::USize)),
+        )],
+    }
+}
+pub fn simd_reduce_all(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_reduce_all");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let Some(mask) = sig.inputs()[0].as_simdvector() else {
+            todo!("Can't simd_reduce_all {mask:?}", mask = sig.inputs()[0])
+        };
+        simd_reduce_mask_impl(*mask, true, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+    let name = asm.alloc_string("simd_reduce_all_lanes");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let elem = asm[sig.inputs()[0].pointed_to().unwrap()];
+        simd_reduce_mask_lanes(elem, true, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+}
+pub fn simd_reduce_any(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_reduce_any");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let Some(mask) = sig.inputs()[0].as_simdvector() else {
+            todo!("Can't simd_reduce_any {mask:?}", mask = sig.inputs()[0])
+        };
+        simd_reduce_mask_impl(*mask, false, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+    let name = asm.alloc_string("simd_reduce_any_lanes");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let elem = asm[sig.inputs()[0].pointed_to().unwrap()];
+        simd_reduce_mask_lanes(elem, false, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+}
+#[test]
+fn reduce_typecheck() {
+    let mut asm = Assembly::default();
+    for elem in [
+        Type::Int(Int::I32),
+        Type::Int(Int::U8),
+        Type::Float(crate::Float::F64),
+    ] {
+        for op in [ReduceOp::Bin(BinOp::Add), ReduceOp::Min, ReduceOp::Max] {
+            let MethodImpl::MethodBody { blocks, locals } = simd_reduce_impl(elem, op, &mut asm)
+            else {
+                panic!()
+            };
+            let elem_ptr = asm.nptr(elem);
+            let sig = asm.sig(
+                [elem_ptr, Type::Int(Int::USize), Type::Int(Int::USize), elem],
+                elem,
+            );
+            for block in &blocks {
+                for root in block.roots() {
+                    asm[*root]
+                        .clone()
+                        .typecheck(sig, &locals, &mut asm)
+                        .unwrap();
+                }
+            }
+        }
+    }
+}
+#[test]
+fn reduce_mask() {
+    let mut asm = Assembly::default();
+    for mask in [
+        SIMDVector::new(Int::I32.into(), 4),
+        SIMDVector::new(Int::I8.into(), 64),
+    ] {
+        for all in [true, false] {
+            let MethodImpl::MethodBody { blocks, .. } = simd_reduce_mask_impl(mask, all, &mut asm)
+            else {
+                panic!()
+            };
+            let CILRoot::Ret(res) = asm[blocks[0].roots()[0]] else {
+                panic!("Expected a return");
+            };
+            let CILNode::BinOp(bits, expected, _) = asm[res] else {
+                panic!("Expected a comparison of the move mask");
+            };
+            let CILNode::Call(info) = &asm[bits] else {
+                panic!("Expected a call to ExtractMostSignificantBits");
+            };
+            assert_eq!(&asm[asm[info.0].name()], "ExtractMostSignificantBits");
+            let CILNode::Const(expected) = &asm[expected] else {
+                panic!("Expected a constant, got {:?}", asm[expected]);
+            };
+            match (*expected.as_ref(), all) {
+                // All lanes set means one bit per lane.
+                (Const::U32(bits), true) => assert_eq!(bits.count_ones(), 4),
+                (Const::U64(bits), true) => assert_eq!(bits, u64::MAX),
+                // Any lane set means the move mask is not 0.
+                (Const::U32(0) | Const::U64(0), false) => (),
+                (other, _) => panic!("Unexpected pattern {other:?}, all:{all}"),
+            }
+        }
+    }
+}
+#[test]
+fn reduce_mask_lanes() {
+    let mut asm = Assembly::default();
+    let elem = Type::Int(Int::I32);
+    let elem_ptr = asm.nptr(elem);
+    let sig = asm.sig([elem_ptr, Type::Int(Int::USize)], Type::Bool);
+    for all in [true, false] {
+        let MethodImpl::MethodBody { blocks, locals } = simd_reduce_mask_lanes(elem, all, &mut asm)
+        else {
+            panic!()
+        };
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+        // Checking every lane gives the result for `all`, stopping early gives the opposite.
+        for (block, expected) in [(3, all), (4, !all)] {
+            let CILRoot::Ret(res) = asm[blocks[block].roots()[0]] else {
+                panic!("Expected a return");
+            };
+            assert_eq!(asm[res], CILNode::Const(Box::new(Const::Bool(expected))));
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use super::{gather::arg_lane_addr, lane_ptr, lane_size, lanes_loop, NEXT_LANE};
+use crate::{
+    v2::{asm::MissingMethodPatcher, cilroot::BranchCond},
+    Assembly, BasicBlock, CILNode, CILRoot, MethodImpl, MethodRefIdx, Type,
+};
+/// Checks that the types of a `simd_select::<M, T>` are consistent: the mask `M` must be a vector of integers, with the same length as the values `T`.
+pub fn validate_select(m_type: Type, t_type: Type) -> Result<(), String> {
+    let Some(mask) = m_type.as_simdvector() else {
+        return Err(format!("simd_select mask {m_type:?} is not a vector."));
+    };
+    let Some(vals) = t_type.as_simdvector() else {
+        return Err(format!("simd_select values {t_type:?} are not a vector."));
+    };
+    if mask.count() != vals.count() {
+        return Err(format!(
+            "simd_select mask has {mask_len} lanes, but the values have {vals_len} lanes.",
+            mask_len = mask.count(),
+            vals_len = vals.count()
+        ));
+    }
+    if Type::from(mask.elem()).as_int().is_none() {
+        return Err(format!(
+            "simd_select mask elements {mask_elem:?} are not integers.",
+            mask_elem = Type::from(mask.elem())
+        ));
+    }
+    Ok(())
+}
+/// Body of `simd_select::<M, T>(mask: M, if_true: T, if_false: T) -> T`. The result starts as a copy of `if_false`, and each lane with a non-zero mask
+/// gets overwritten with the lane of `if_true`.
+///
+/// Each lane `lane` gets 2 blocks: `1 + 2 * lane` checks the mask, and `2 + 2 * lane` copies the lane of `if_true`. Block `1 + 2 * N` returns.
+fn simd_select_impl(m_type: Type, t_type: Type, asm: &mut Assembly) -> MethodImpl {
+    if let Err(err) = validate_select(m_type, t_type) {
+        panic!("{err}")
+    }
+    let mask = *m_type.as_simdvector().unwrap();
+    let vals = *t_type.as_simdvector().unwrap();
+    let elem = Type::from(vals.elem());
+    let elem_idx = asm.alloc_type(elem);
+    let mask_elem = Type::from(mask.elem());
+    let mask_elem_idx = asm.alloc_type(mask_elem);
+    let ret_block = 1 + 2 * u32::from(vals.count());
+    let if_false = asm.alloc_node(CILNode::LdArg(2));
+    let start = vec![
+        asm.alloc_root(CILRoot::StLoc(0, if_false)),
+        asm.alloc_root(CILRoot::Branch(Box::new((1, 0, None)))),
+    ];
+    let mut blocks = vec![BasicBlock::new(start, 0, None)];
+    for lane in 0..u64::from(vals.count()) {
+        let check_block = 1 + 2 * u32::try_from(lane).unwrap();
+        let copy_block = check_block + 1;
+        let next_block = check_block + 2;
+        let mask_addr = arg_lane_addr(0, lane, lane_size(mask), mask_elem, asm);
+        let mask_val = asm.alloc_node(CILNode::LdInd {
+            addr: mask_addr,
+            tpe: mask_elem_idx,
+            volatile: false,
+        });
+        let skip = asm.alloc_root(CILRoot::Branch(Box::new((
+            next_block,
+            0,
+            Some(BranchCond::False(mask_val)),
+        ))));
+        let copy = asm.alloc_root(CILRoot::Branch(Box::new((copy_block, 0, None))));
+        blocks.push(BasicBlock::new(vec![skip, copy], check_block, None));
+        // res[lane] = if_true[lane]
+        let src = arg_lane_addr(1, lane, lane_size(vals), elem, asm);
+        let val = asm.alloc_node(CILNode::LdInd {
+            addr: src,
+            tpe: elem_idx,
+            volatile: false,
+        });
+        let res = asm.alloc_node(CILNode::LdLocA(0));
+        let res = asm.alloc_node(CILNode::RefToPtr(res));
+        let res = asm.alloc_node(CILNode::PtrCast(
+            res,
+            Box::new(crate::cilnode::PtrCastRes::Ptr(elem_idx)),
+        ));
+        let offset = asm.alloc_node(crate::Const::USize(lane * lane_size(vals)));
+        let dst = asm.biop(res, offset, crate::BinOp::Add);
+        let dst = asm.alloc_node(dst);
+        let store = asm.alloc_root(CILRoot::StInd(Box::new((dst, val, elem, false))));
+        let next = asm.alloc_root(CILRoot::Branch(Box::new((next_block, 0, None))));
+        blocks.push(BasicBlock::new(vec![store, next], copy_block, None));
+    }
+    let res = asm.alloc_node(CILNode::LdLoc(0));
+    let ret = asm.alloc_root(CILRoot::Ret(res));
+    blocks.push(BasicBlock::new(vec![ret], ret_block, None));
+    MethodImpl::MethodBody {
+        blocks,
+        locals: vec![(None, asm.alloc_type(t_type))],
+    }
+}
+/// Body of `simd_select_lanes(dst: *mut T, mask: *const M, if_true: *const T, if_false: *const T, count: usize)`, which selects `count` lanes of type `T`,
+/// one by one. Used for vectors with no .NET vector type(eg. with 3 lanes).
+fn ptr_select(mask_elem: Type, elem: Type, asm: &mut Assembly) -> MethodImpl {
+    let elem_idx = asm.alloc_type(elem);
+    let mask_elem_idx = asm.alloc_type(mask_elem);
+    let lane_roots = |lane, asm: &mut Assembly| {
+        let [if_true, if_false] = [2, 3].map(|arg| {
+            let addr = lane_ptr(arg, elem, lane, asm);
+            asm.alloc_node(CILNode::LdInd {
+                addr,
+                tpe: elem_idx,
+                volatile: false,
+            })
+        });
+        let mask_addr = lane_ptr(1, mask_elem, lane, asm);
+        let mask_val = asm.alloc_node(CILNode::LdInd {
+            addr: mask_addr,
+            tpe: mask_elem_idx,
+            volatile: false,
+        });
+        // dst[lane] = if mask[lane] != 0 { if_true[lane] } else { if_false[lane] }
+        let dst = lane_ptr(0, elem, lane, asm);
+        let set_false = asm.alloc_root(CILRoot::StInd(Box::new((dst, if_false, elem, false))));
+        let skip = asm.alloc_root(CILRoot::Branch(Box::new((
+            NEXT_LANE,
+            0,
+            Some(BranchCond::False(mask_val)),
+        ))));
+        let set_true = asm.alloc_root(CILRoot::StInd(Box::new((dst, if_true, elem, false))));
+        vec![set_false, skip, set_true]
+    };
+    lanes_loop(4, lane_roots, asm)
+}
+pub fn simd_select(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+    let name = asm.alloc_string("simd_select");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let inputs = sig.inputs();
+        simd_select_impl(inputs[0], inputs[1], asm)
+    };
+    patcher.insert(name, Box::new(generator));
+    let name = asm.alloc_string("simd_select_lanes");
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let mask_elem = asm[sig.inputs()[1].pointed_to().unwrap()];
+        let elem = asm[sig.inputs()[0].pointed_to().unwrap()];
+        ptr_select(mask_elem, elem, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+}
+#[test]
+fn select_validation() {
+    use crate::{tpe::simd::SIMDElem, Float, Int};
+    let i32x4 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Int(Int::I32), 4));
+    let i64x2 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Int(Int::I64), 2));
+    let i32x2 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Int(Int::I32), 2));
+    let f32x4 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Float(Float::F32), 4));
+    assert_eq!(validate_select(i32x4, f32x4), Ok(()));
+    // The mask and value lengths differ.
+    assert!(validate_select(i64x2, f32x4).is_err());
+    assert!(validate_select(i32x2, i32x4).is_err());
+    // The mask elements are not integers.
+    assert!(validate_select(f32x4, i32x4).is_err());
+    // The mask is not a vector.
+    assert!(validate_select(Type::Int(Int::I32), i32x4).is_err());
+}
+#[test]
+fn select_typecheck() {
+    use crate::{tpe::simd::SIMDElem, Float, Int};
+    let mut asm = Assembly::default();
+    let i32x4 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Int(Int::I32), 4));
+    let f32x4 = Type::SIMDVector(super::SIMDVector::new(SIMDElem::Float(Float::F32), 4));
+    let MethodImpl::MethodBody { blocks, locals } = simd_select_impl(i32x4, f32x4, &mut asm) else {
+        panic!()
+    };
+    assert_eq!(blocks.len(), 1 + 4 * 2 + 1);
+    let sig = asm.sig([i32x4, f32x4, f32x4], f32x4);
+    for block in &blocks {
+        for root in block.roots() {
+            asm[*root]
+                .clone()
+                .typecheck(sig, &locals, &mut asm)
+                .unwrap();
+        }
+    }
+}
+#[test]
+fn ptr_select_typecheck() {
+    use crate::{Float, Int};
+    let mut asm = Assembly::default();
+    let mask_elem = Type::Int(Int::I32);
+    let elem = Type::Float(Float::F32);
+    let MethodImpl::MethodBody { blocks, locals } = ptr_select(mask_elem, elem, &mut asm) else {
+        panic!()
+    };
+    let mask_ptr = asm.nptr(mask_elem);
+    let elem_ptr = asm.nptr(elem);
+    let sig = asm.sig(
+        [
+            elem_ptr,
+            mask_ptr,
+            elem_ptr,
+            elem_ptr,
+            Type::Int(Int::USize),
+        ],
+        Type::Void,
+    );
+    for block in &blocks {
+        for root in block.roots() {
+            asm[*root]
+                .clone()
+                .typecheck(sig, &locals, &mut asm)
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use super::{lane_ptr, lanes_loop, needs_scalar_operators, scalar_class};
+use crate::{
+    cilnode::{MethodKind, UnOp},
+    v2::asm::MissingMethodPatcher,
+    Assembly, BasicBlock, CILNode, CILRoot, MethodImpl, MethodRef, MethodRefIdx, NodeIdx, Type,
+};
+/// Applies a unary operation to a scalar lane of type `elem`. Operations with a CIL instruction(`op`) use it, unless the lane has no primitive CIL arithmetic
+/// (`i128`, `u128` and `f16`). All other operations call the static method `op_scalar`(eg. `op_UnaryNegation` or `Sqrt`) of the lane type.
+fn scalar_unop(
+    val: NodeIdx,
+    elem: Type,
+    op: Option<UnOp>,
+    op_scalar: &str,
+    asm: &mut Assembly,
+) -> NodeIdx {
+    let val = match op {
+        Some(op) if !needs_scalar_operators(elem) => CILNode::UnOp(val, op),
+        _ => {
+            let scalar_class = scalar_class(elem, asm);
+            let sig = asm.sig([elem], elem);
+            let op_scalar = asm.alloc_string(op_scalar);
+            let op_scalar = asm.alloc_methodref(MethodRef::new(
+                scalar_class,
+                op_scalar,
+                sig,
+                MethodKind::Static,
+                vec![].into(),
+            ));
+            CILNode::Call(Box::new((op_scalar, [val].into())))
+        }
+    };
+    asm.alloc_node(val)
+}
+/// Body of `simd_*_lanes(dst: *mut T, src: *const T, count: usize)`, which applies a unary operation to `count` lanes of type `T`, one by one.
+/// Used for vectors with no .NET vector type(eg. with 3 lanes).
+fn ptr_unop(elem: Type, op: Option<UnOp>, op_scalar: &str, asm: &mut Assembly) -> MethodImpl {
+    let elem_idx = asm.alloc_type(elem);
+    let lane_roots = |lane, asm: &mut Assembly| {
+        // dst[lane] = op src[lane]
+        let addr = lane_ptr(1, elem, lane, asm);
+        let val = asm.alloc_node(CILNode::LdInd {
+            addr,
+            tpe: elem_idx,
+            volatile: false,
+        });
+        let val = scalar_unop(val, elem, op.clone(), op_scalar, asm);
+        let dst = lane_ptr(0, elem, lane, asm);
+        vec![asm.alloc_root(CILRoot::StInd(Box::new((dst, val, elem, false))))]
+    };
+    lanes_loop(2, lane_roots, asm)
+}
+/// Registers `{op_name}_lanes`, the variant of a unop used for vectors with no .NET vector type. See [`ptr_unop`].
+fn lanes_unop(
+    op_name: &str,
+    op: Option<UnOp>,
+    op_scalar: &'static str,
+    asm: &mut Assembly,
+    patcher: &mut MissingMethodPatcher,
+) {
+    let name = asm.alloc_string(format!("{op_name}_lanes"));
+    let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+        let sig = asm[asm[mref].sig()].clone();
+        let elem = asm[sig.inputs()[0].pointed_to().unwrap()];
+        ptr_unop(elem, op.clone(), op_scalar, asm)
+    };
+    patcher.insert(name, Box::new(generator));
+}
+macro_rules! unop {
+    ($op_name:ident,$op_dotnet:literal,$op:expr,$op_scalar:literal) => {
+        pub fn $op_name(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+            let name = asm.alloc_string(stringify!($op_name));
+            let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+                let sig = asm[asm[mref].sig()].clone();
+
+                let Some(vec_type) = sig.inputs()[0].as_simdvector() else {
+                    let name = stringify!($op_name);
+                    todo!("Can't {name} {vec_type:?} ", vec_type = sig.inputs()[0])
+                };
+                let elem: Type = vec_type.elem().into();
+                let extension_class = vec_type.extension_class(asm);
+                let extension_class = asm[extension_class].clone();
+                let op = asm.alloc_string($op_dotnet);
+                // Generic vec
+                let generic_class = vec_type.class(asm);
+                let mut generic_class = asm[generic_class].clone();
+                generic_class.set_generics(vec![Type::PlatformGeneric(
+                    0,
+                    crate::tpe::GenericKind::CallGeneric,
+                )]);
+                let generic_class = asm.alloc_class_ref(generic_class);
+                let op = extension_class.static_mref_generic(
+                    &[Type::ClassRef(generic_class)],
+                    Type::ClassRef(generic_class),
+                    op,
+                    asm,
+                    [elem].into(),
+                );
+                let val = asm.alloc_node(CILNode::LdArg(0));
+                let res = asm.alloc_node(CILNode::Call(Box::new((op, [val].into()))));
+                let ret = asm.alloc_root(CILRoot::Ret(res));
+                MethodImpl::MethodBody {
+                    blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+                    locals: vec![],
+                }
+            };
+            patcher.insert(name, Box::new(generator));
+            lanes_unop(stringify!($op_name), $op, $op_scalar, asm, patcher);
+        }
+    };
+    // Some operations(like `Ceiling`) are not generic, and only have overloads for float vectors.
+    ($op_name:ident,$op_dotnet:literal,$op:expr,$op_scalar:literal,non_generic) => {
+        pub fn $op_name(asm: &mut Assembly, patcher: &mut MissingMethodPatcher) {
+            let name = asm.alloc_string(stringify!($op_name));
+            let generator = move |mref: MethodRefIdx, asm: &mut Assembly| {
+                let sig = asm[asm[mref].sig()].clone();
+
+                let Some(vec_type) = sig.inputs()[0].as_simdvector() else {
+                    let name = stringify!($op_name);
+                    todo!("Can't {name} {vec_type:?} ", vec_type = sig.inputs()[0])
+                };
+                let extension_class = vec_type.extension_class(asm);
+                let extension_class = asm[extension_class].clone();
+                let op = asm.alloc_string($op_dotnet);
+                let op = extension_class.static_mref(
+                    &[Type::SIMDVector(*vec_type)],
+                    Type::SIMDVector(*vec_type),
+                    op,
+                    asm,
+                );
+                let val = asm.alloc_node(CILNode::LdArg(0));
+                let res = asm.alloc_node(CILNode::Call(Box::new((op, [val].into()))));
+                let ret = asm.alloc_root(CILRoot::Ret(res));
+                MethodImpl::MethodBody {
+                    blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+                    locals: vec![],
+                }
+            };
+            patcher.insert(name, Box::new(generator));
+            lanes_unop(stringify!($op_name), $op, $op_scalar, asm, patcher);
+        }
+    };
+}
+unop!(
+    simd_ones_compliment,
+    "OnesComplement",
+    Some(UnOp::Not),
+    "op_OnesComplement"
+);
+unop!(simd_neg, "Negate", Some(UnOp::Neg), "op_UnaryNegation");
+unop!(simd_abs, "Abs", None, "Abs");
+unop!(simd_sqrt, "Sqrt", None, "Sqrt");
+unop!(simd_ceil, "Ceiling", None, "Ceiling", non_generic);
+unop!(simd_floor, "Floor", None, "Floor", non_generic);
+#[test]
+fn ptr_unop_typecheck() {
+    use crate::{Float, Int};
+    let mut asm = Assembly::default();
+    for (elem, op, op_scalar) in [
+        (Type::Int(Int::I32), Some(UnOp::Neg), "op_UnaryNegation"),
+        (Type::Int(Int::I128), Some(UnOp::Neg), "op_UnaryNegation"),
+        (Type::Int(Int::U8), Some(UnOp::Not), "op_OnesComplement"),
+        (Type::Float(Float::F32), None, "Sqrt"),
+    ] {
+        let MethodImpl::MethodBody { blocks, locals } = ptr_unop(elem, op, op_scalar, &mut asm)
+        else {
+            panic!()
+        };
+        let elem_ptr = asm.nptr(elem);
+        let sig = asm.sig([elem_ptr, elem_ptr, Type::Int(Int::USize)], Type::Void);
+        for block in &blocks {
+            for root in block.roots() {
+                asm[*root]
+                    .clone()
+                    .typecheck(sig, &locals, &mut asm)
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -19,7 +19,7 @@ impl Exporter for CillyIRExpoter {
         };
         let mut il_out = std::io::BufWriter::new(std::fs::File::create(&il_path)?);
         writeln!(il_out, "fn add_cilly(asm:&mut Assembly){{")?;
-        for def in asm.class_defs().values() {
+        for (_, def) in asm.class_defs() {
             let name = &asm[def.name()];
             let escaped_name = escape_class_name(name);
             writeln!(il_out, "fn {escaped_name}(asm:&mut Assembly){{",)?;
@@ -751,6 +751,26 @@ impl CILNode {
                 Self::Call(Box::new((callargs.site, args)))
             }
             V1Node::NewObj(callargs) => {
+                // `NewObj` is lowered to a call to a constructor, so the site must have constructor semantics.
+                let site = &asm[callargs.site];
+                assert_eq!(
+                    site.kind(),
+                    MethodKind::Constructor,
+                    "NewObj target {name:?} is not a constructor.",
+                    name = &asm[site.name()]
+                );
+                assert_eq!(
+                    *asm[site.sig()].output(),
+                    Type::Void,
+                    "NewObj target {name:?} has a non-void return type.",
+                    name = &asm[site.name()]
+                );
+                assert_eq!(
+                    site.stack_inputs(asm).len(),
+                    callargs.args.len(),
+                    "NewObj target {name:?} called with a wrong number of arguments.",
+                    name = &asm[site.name()]
+                );
                 let args: Box<[_]> = callargs
                     .args
                     .iter()
@@ -812,20 +832,32 @@ impl CILNode {
                 let tpe = asm.alloc_type(*tpe.as_ref());
                 CILNode::LocAllocAlgined { tpe, align: *align }
             }
+            // Should be resolved before the conversion, but can be lowered here too.
+            V1Node::PointerToConstValue(bytes) => {
+                let sfld = asm.const_value_static(**bytes);
+                Self::LdStaticFieldAdress(asm.alloc_sfld(sfld))
+            }
             V1Node::AddressOfStaticField(sfld) => Self::LdStaticFieldAdress(asm.alloc_sfld(**sfld)),
             V1Node::LDStaticField(sfld) => Self::LdStaticField(asm.alloc_sfld(**sfld)),
             V1Node::LDFtn(method_ref) => Self::LdFtn(*method_ref),
             V1Node::Volatile(inner) => {
-                let mut tmp = Self::from_v1(inner, asm);
-                if let Self::LdInd {
-                    volatile: volitale, ..
-                } = &mut tmp
-                {
-                    *volitale = true;
-                } else {
-                    panic!()
+                match Self::from_v1(inner, asm) {
+                    Self::LdInd { addr, tpe, .. } => Self::LdInd {
+                        addr,
+                        tpe,
+                        volatile: true,
+                    },
+                    // There is no volatile field load, so this is lowered to a volatile load from the field address.
+                    Self::LdField { addr, field } => {
+                        let tpe = asm.alloc_type(asm[field].tpe());
+                        Self::LdInd {
+                            addr: asm.alloc_node(Self::LdFieldAdress { addr, field }),
+                            tpe,
+                            volatile: true,
+                        }
+                    }
+                    tmp => panic!("Only indirect and field loads can be volatile, but {tmp:?} was marked as volatile."),
                 }
-                tmp
             }
             V1Node::LDLen { arr } => {
                 let arr = Self::from_v1(arr, asm);
@@ -1020,3 +1052,118 @@ impl CILNode {
         }
     }
 }
+#[test]
+fn from_v1_bool_char_casts() {
+    let mut asm = Assembly::default();
+    // `char` is represented as a `u32`, so casts to it target `u32`.
+    for (val, conv, target) in [
+        // `bool` to `u8`
+        (
+            Const::Bool(true),
+            V1Node::ConvU8 as fn(Box<V1Node>) -> V1Node,
+            Int::U8,
+        ),
+        // `u32` to `char`
+        (Const::U32(0x10FFFF), V1Node::ConvU32, Int::U32),
+        // `u8` to `char`
+        (Const::U8(0xE9), V1Node::ConvU32, Int::U32),
+        // `bool` to `u64`
+        (Const::Bool(true), V1Node::ZeroExtendToU64, Int::U64),
+    ] {
+        let val = asm.alloc_node(val);
+        let cast = CILNode::from_v1(&conv(Box::new(V1Node::V2(val))), &mut asm);
+        assert_eq!(
+            cast,
+            CILNode::IntCast {
+                input: val,
+                target,
+                extend: ExtendKind::ZeroExtend
+            }
+        );
+    }
+}
+#[test]
+fn from_v1_ld_obj_simd() {
+    use super::tpe::simd::{SIMDElem, SIMDVector};
+    let mut asm = Assembly::default();
+    let f32x4 = Type::SIMDVector(SIMDVector::new(SIMDElem::Float(Float::F32), 4));
+    let ptr_tpe = asm.nptr(f32x4);
+    let sig = asm.sig([ptr_tpe], f32x4);
+    let ptr = asm.alloc_node(CILNode::LdArg(0));
+    let load = CILNode::from_v1(
+        &V1Node::LdObj {
+            ptr: Box::new(V1Node::V2(ptr)),
+            obj: Box::new(f32x4),
+        },
+        &mut asm,
+    );
+    // The whole vector is loaded, as a vector value.
+    assert_eq!(
+        load,
+        CILNode::LdInd {
+            addr: ptr,
+            tpe: asm.alloc_type(f32x4),
+            volatile: false,
+        }
+    );
+    assert_eq!(load.typecheck(sig, &[], &mut asm).unwrap(), f32x4);
+}
+#[test]
+fn from_v1_const_value_ptr() {
+    let mut asm = Assembly::default();
+    // A `u32x4` constant.
+    let bytes = u128::from_le_bytes(*b"\x01\0\0\0\x02\0\0\0\x03\0\0\0\x04\0\0\0");
+    let ptr = CILNode::from_v1(&V1Node::PointerToConstValue(Box::new(bytes)), &mut asm);
+    let CILNode::LdStaticFieldAdress(sfld) = ptr else {
+        panic!("Unexpected lowering {ptr:?}");
+    };
+    let expected = asm.const_value_static(bytes);
+    assert_eq!(asm[sfld], expected);
+    // The constant is the same field on every use.
+    assert_eq!(
+        CILNode::from_v1(&V1Node::PointerToConstValue(Box::new(bytes)), &mut asm),
+        ptr
+    );
+}
+#[test]
+fn from_v1_new_obj() {
+    use crate::cil_node::CallOpArgs;
+    let mut asm = Assembly::default();
+    let exception = ClassRef::exception(&mut asm);
+    let ctor = asm[exception]
+        .clone()
+        .ctor(&[Type::PlatformString], &mut asm);
+    let msg = asm.alloc_string("msg");
+    let msg = asm.alloc_node(Const::PlatformString(msg));
+    let new_obj = CILNode::from_v1(
+        &V1Node::NewObj(Box::new(CallOpArgs {
+            site: ctor,
+            args: [V1Node::V2(msg)].into(),
+        })),
+        &mut asm,
+    );
+    assert_eq!(new_obj, CILNode::Call(Box::new((ctor, [msg].into()))));
+}
+#[test]
+#[should_panic(expected = "is not a constructor")]
+fn from_v1_new_obj_not_ctor() {
+    use crate::cil_node::CallOpArgs;
+    let mut asm = Assembly::default();
+    let exception = ClassRef::exception(&mut asm);
+    let name = asm.alloc_string("Create");
+    let sig = asm.sig([], Type::ClassRef(exception));
+    let site = asm.alloc_methodref(super::MethodRef::new(
+        exception,
+        name,
+        sig,
+        MethodKind::Static,
+        [].into(),
+    ));
+    CILNode::from_v1(
+        &V1Node::NewObj(Box::new(CallOpArgs {
+            site,
+            args: [].into(),
+        })),
+        &mut asm,
+    );
+}
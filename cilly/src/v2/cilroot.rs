@@ -455,13 +455,20 @@ impl CILRoot {
                 source: *source,
             },
             V1Root::Volatile(inner) => {
-                let mut tmp = Self::from_v1(inner, asm);
-                if let Self::StInd(inner) = &mut tmp {
-                    inner.3 = true;
-                } else {
-                    panic!()
+                match Self::from_v1(inner, asm) {
+                    Self::StInd(mut inner) => {
+                        inner.3 = true;
+                        Self::StInd(inner)
+                    }
+                    // There is no volatile field store, so this is lowered to a volatile store to the field address.
+                    Self::SetField(info) => {
+                        let (field, addr, val) = *info;
+                        let tpe = asm[field].tpe();
+                        let addr = asm.alloc_node(CILNode::LdFieldAdress { addr, field });
+                        Self::StInd(Box::new((addr, val, tpe, true)))
+                    }
+                    tmp => panic!("Only indirect and field stores can be volatile, but {tmp:?} was marked as volatile."),
                 }
-                tmp
             }
             V1Root::ReThrow => Self::ReThrow,
             V1Root::SetStaticField { descr, value } => {
@@ -790,3 +797,45 @@ fn test_many_mut() {
         assert_eq!(many_mut(&mut vec).len(), i);
     }
 }
+#[test]
+fn from_v1_volatile_store() {
+    use super::{Const, FieldDesc};
+    use crate::cil_node::CILNode as V1Node;
+    let mut asm = Assembly::default();
+    let addr = asm.alloc_node(CILNode::LdArg(0));
+    let val = asm.alloc_node(Const::I32(7));
+    let store = CILRoot::from_v1(
+        &V1Root::Volatile(Box::new(V1Root::STIndI32(
+            V1Node::V2(addr),
+            V1Node::V2(val),
+        ))),
+        &mut asm,
+    );
+    assert_eq!(
+        store,
+        CILRoot::StInd(Box::new((addr, val, Type::Int(Int::I32), true)))
+    );
+    // Volatile field stores become volatile stores to the address of that field.
+    let main_module = *asm.main_module();
+    let name = asm.alloc_string("f");
+    let field = asm.alloc_field(FieldDesc::new(main_module, name, Type::Int(Int::I32)));
+    let store = CILRoot::from_v1(
+        &V1Root::Volatile(Box::new(V1Root::SetField {
+            addr: Box::new(V1Node::V2(addr)),
+            value: Box::new(V1Node::V2(val)),
+            desc: field,
+        })),
+        &mut asm,
+    );
+    let field_addr = asm.alloc_node(CILNode::LdFieldAdress { addr, field });
+    assert_eq!(
+        store,
+        CILRoot::StInd(Box::new((field_addr, val, Type::Int(Int::I32), true)))
+    );
+}
+#[test]
+#[should_panic(expected = "was marked as volatile")]
+fn from_v1_volatile_invalid() {
+    let mut asm = Assembly::default();
+    CILRoot::from_v1(&V1Root::Volatile(Box::new(V1Root::Nop)), &mut asm);
+}
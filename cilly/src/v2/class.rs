@@ -302,6 +302,13 @@ impl ClassRef {
         let asm_name = Some(asm.alloc_string("System.Runtime"));
         asm.alloc_class_ref(ClassRef::new(name, asm_name, false, [].into()))
     }
+    /// Returns a reference to the class `System.BitConverter`
+    #[must_use]
+    pub fn bit_converter(asm: &mut Assembly) -> ClassRefIdx {
+        let name: StringIdx = asm.alloc_string("System.BitConverter");
+        let asm_name = Some(asm.alloc_string("System.Runtime"));
+        asm.alloc_class_ref(ClassRef::new(name, asm_name, false, [].into()))
+    }
     /// Returns a reference to the class `System.Buffers.Binary.BinaryPrimitives`
     #[must_use]
     pub fn binary_primitives(asm: &mut Assembly) -> ClassRefIdx {
@@ -813,9 +820,9 @@ fn type_gc() {
         None,
         None,
     ));
-    assert_eq!(asm.class_defs().len(), 2);
+    assert_eq!(asm.class_defs().count(), 2);
     asm.eliminate_dead_types();
-    assert_eq!(asm.class_defs().len(), 1);
+    assert_eq!(asm.class_defs().count(), 1);
 }
 #[test]
 fn merge_defs() {
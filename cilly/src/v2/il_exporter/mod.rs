@@ -8,10 +8,12 @@ use super::{
     cilroot::BranchCond,
     method::LocalDef,
     tpe::simd::SIMDElem,
-    Assembly, BinOp, CILIter, CILIterElem, CILNode, ClassRefIdx, Exporter, Int, MethodDefIdx,
-    NodeIdx, RootIdx, SigIdx, Type,
+    Assembly, BinOp, CILIter, CILIterElem, CILNode, ClassRefIdx, Exporter, Int, MethodDef,
+    MethodDefIdx, NodeIdx, RootIdx, SigIdx, Type,
 };
 
+/// The dangling address returned by a zero-sized `LocAlloc`. Aligned enough for any type, including SIMD vectors.
+const ZERO_SIZE_LOCALLOC_ADDR: u8 = 64;
 pub struct ILExporter {
     flavour: IlasmFlavour,
     is_lib: bool,
@@ -23,7 +25,6 @@ impl ILExporter {
     }
 
     fn export_to_write(&self, asm: &super::Assembly, out: &mut impl Write) -> std::io::Result<()> {
-        let asm_mut = &mut asm.clone();
         writeln!(out, ".assembly _{{}}")?;
         // Iterate trough all types
         for class_def in asm.iter_class_defs() {
@@ -104,20 +105,7 @@ impl ILExporter {
                     crate::v2::cilnode::MethodKind::Virtual => "virtual instance",
                     crate::v2::cilnode::MethodKind::Constructor => "rtspecialname specialname",
                 };
-                let pinvoke = if let MethodImpl::Extern {
-                    lib,
-                    preserve_errno,
-                } = method.implementation()
-                {
-                    let lib = &asm[*lib];
-                    if *preserve_errno {
-                        format!("pinvokeimpl(\"{lib}\" cdecl lasterr)")
-                    } else {
-                        format!("pinvokeimpl(\"{lib}\" cdecl)")
-                    }
-                } else {
-                    String::new()
-                };
+                let pinvoke = pinvoke_attr(method.implementation(), asm);
                 let name = &asm[method.name()];
                 let sig = &asm[method.sig()];
                 let ret = type_il(sig.output(), asm);
@@ -134,9 +122,9 @@ impl ILExporter {
                     .zip(method.arg_names())
                     .map(|(tpe, name)| match name {
                         Some(name) => {
-                            format!("{} '{}'", non_void_type_il(tpe, asm_mut), &asm_mut[*name])
+                            format!("{} '{}'", non_void_type_il(tpe, asm), &asm[*name])
                         }
-                        None => non_void_type_il(tpe, asm_mut),
+                        None => non_void_type_il(tpe, asm),
                     })
                     .intersperse(",".to_string())
                     .collect();
@@ -150,14 +138,12 @@ impl ILExporter {
                     ".method {vis} hidebysig {kind} {pinvoke} {ret} '{name}'({inputs}) cil managed {preservesig}{{// Method ID {method_id:?}"
                 )?;
                 debug_assert!(ensure_unqiue.insert(*method_id));
-                let stack_size = match method.resolved_implementation(asm_mut) {
+                let stack_size = match method.resolved_implementation(asm) {
                     MethodImpl::MethodBody { blocks, .. } => blocks
                         .iter()
                         .flat_map(|block| block.roots().iter())
                         .map(|root| {
-                            crate::v2::CILIter::new(asm_mut.get_root(*root).clone(), asm_mut)
-                                .count()
-                                + 10
+                            crate::v2::CILIter::new(asm.get_root(*root).clone(), asm).count() + 10
                         })
                         .max()
                         .unwrap_or(0),
@@ -172,8 +158,8 @@ impl ILExporter {
                     writeln!(out, ".entrypoint")?;
                 }
                 // Export the implementation
-                let mimpl = method.resolved_implementation(asm_mut).clone();
-                self.export_method_imp(asm_mut, out, &mimpl, name, method.sig())?;
+                let mimpl = method.resolved_implementation(asm).clone();
+                self.export_method_imp(asm, out, &mimpl, name, method.sig())?;
                 writeln!(out, "}}")?;
             }
             writeln!(out, "}}")?;
@@ -181,9 +167,19 @@ impl ILExporter {
 
         Ok(())
     }
+    /// Writes the ilasm-like text of a single method body (locals, blocks and instructions) into a `String`.
+    /// Used for debugging - the result is not a complete, assemblable IL file.
+    pub(crate) fn dump_method(&self, asm: &super::Assembly, method: &MethodDef) -> String {
+        let mut out = Vec::new();
+        let name = asm[method.name()].to_owned();
+        let mimpl = method.resolved_implementation(asm).clone();
+        self.export_method_imp(asm, &mut out, &mimpl, &name, method.sig())
+            .expect("Writing to a `Vec<u8>` can't fail");
+        String::from_utf8(out).expect("IL exporter produced invalid UTF-8")
+    }
     fn export_method_imp(
         &self,
-        asm: &mut super::Assembly,
+        asm: &super::Assembly,
         out: &mut impl Write,
         mimpl: &MethodImpl,
         name: &str,
@@ -234,14 +230,15 @@ impl ILExporter {
             MethodImpl::AliasFor(_) => {
                 panic!("resolved_implementation returned `AliasFor`")
             }
-            MethodImpl::Missing =>writeln!(out,"ldstr \"missing methiod {name}\"\n newobj void [System.Runtime] System.Exception::.ctor(string)\n throw")?,
+            // A method without a body is not valid CIL, so emit a stub which throws when called.
+            MethodImpl::Missing =>writeln!(out,"ldstr \"missing method {name}\"\n newobj instance void [System.Runtime]System.NotImplementedException::.ctor(string)\n throw")?,
         };
         Ok(())
     }
     #[allow(clippy::only_used_in_recursion)] // Futrue proffing. The IL exporter will need this in the future.
     fn export_node(
         &self,
-        asm: &mut super::Assembly,
+        asm: &super::Assembly,
         out: &mut impl Write,
         node: NodeIdx,
         sig: SigIdx,
@@ -456,17 +453,7 @@ impl ILExporter {
                     .map(|tpe| non_void_type_il(tpe, asm))
                     .intersperse(",".to_owned())
                     .collect();
-                let generic = if mref.generics().is_empty() {
-                    "".to_string()
-                } else {
-                    let generic_list: String = mref
-                        .generics()
-                        .iter()
-                        .map(|tpe| type_il(tpe, asm))
-                        .intersperse(",".to_owned())
-                        .collect();
-                    format!("<{generic_list}>")
-                };
+                let generic = generics_il(mref.generics(), asm);
                 let name = &asm[mref.name()];
                 let class = class_ref(mref.class(), asm);
                 writeln!(
@@ -695,6 +682,13 @@ impl ILExporter {
                 writeln!(out, "calli {output} ({inputs})")
             }
             CILNode::LocAlloc { size } => {
+                // `localloc` of 0 bytes may return null, but `alloca(0)` must give a non-null, well aligned pointer.
+                // Nothing can be stored behind it, so a dangling address is enough.
+                if let CILNode::Const(cst) = &asm[size] {
+                    if cst.is_zero() {
+                        return writeln!(out, "ldc.i4.s {ZERO_SIZE_LOCALLOC_ADDR} conv.u");
+                    }
+                }
                 self.export_node(asm, out, size, sig, locals)?;
                 writeln!(out, "localloc")
             }
@@ -764,7 +758,7 @@ impl ILExporter {
     #[allow(clippy::too_many_arguments)]
     fn export_root(
         &self,
-        asm: &mut super::Assembly,
+        asm: &super::Assembly,
         out: &mut impl Write,
         root: RootIdx,
         is_handler: bool,
@@ -1068,12 +1062,13 @@ impl ILExporter {
                     .map(|tpe| non_void_type_il(tpe, asm))
                     .intersperse(",".to_owned())
                     .collect();
+                let generic = generics_il(mref.generics(), asm);
                 let name = &asm[mref.name()];
                 let class = class_ref(mref.class(), asm);
 
                 writeln!(
                     out,
-                    "{call_op} {output} {class}::'{name}'({inputs}) //mref:{:?}",
+                    "{call_op} {output} {class}::'{name}'{generic}({inputs}) //mref:{:?}",
                     call.0
                 )
             }
@@ -1141,7 +1136,7 @@ impl ILExporter {
                     Type::Void => writeln!(out, "pop pop ldstr \"Attempted to wrtie to a zero-sized type(void).\" newobj void [System.Runtime]System.Exception::.ctor(string) throw"), // TODO: forbid this, since this is NEVER valid.
                     Type::PlatformArray { .. } => writeln!(out, "{is_volitale} stind.ref"),
                     Type::FnPtr(_) => writeln!(out, "{is_volitale} stind.i"),
-                    Type::SIMDVector(_)=>writeln!(out, "stobj {}", type_il(&tpe, asm)),
+                    Type::SIMDVector(_)=>writeln!(out, "{is_volitale} stobj {}", type_il(&tpe, asm)),
                 }
             }
             super::CILRoot::InitBlk(blk) => {
@@ -1307,6 +1302,37 @@ impl Exporter for ILExporter {
         Ok(())
     }
 }
+/// Returns the `pinvokeimpl` attribute of an extern method, or an empty string for methods defined in this assembly.
+/// Methods which may set errno get the `lasterr` flag(the IL equivalent of `SetLastError = true`), which makes the runtime
+/// save errno right after the call, before it gets a chance to clobber it.
+fn pinvoke_attr(mimpl: &MethodImpl, asm: &Assembly) -> String {
+    if let MethodImpl::Extern {
+        lib,
+        preserve_errno,
+    } = mimpl
+    {
+        let lib = &asm[*lib];
+        if *preserve_errno {
+            format!("pinvokeimpl(\"{lib}\" cdecl lasterr)")
+        } else {
+            format!("pinvokeimpl(\"{lib}\" cdecl)")
+        }
+    } else {
+        String::new()
+    }
+}
+/// Returns the generic instantiation of a method call site(eg. `<int32,float64>`), or an empty string for non-generic methods.
+fn generics_il(generics: &[Type], asm: &Assembly) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    let generic_list: String = generics
+        .iter()
+        .map(|tpe| type_il(tpe, asm))
+        .intersperse(",".to_owned())
+        .collect();
+    format!("<{generic_list}>")
+}
 fn simple_class_ref(cref: ClassRefIdx, asm: &Assembly) -> String {
     let cref = asm.class_ref(cref);
     let name = &asm[cref.name()];
@@ -1464,3 +1490,243 @@ static RUNTIME_CONFIG: std::sync::LazyLock<String> = std::sync::LazyLock::new(||
       }}"
     )
 });
+#[test]
+fn pinvoke_preserve_errno() {
+    use super::{cilnode::MethodKind, MethodRef};
+    let mut asm = Assembly::default();
+    let main_module = *asm.main_module();
+    let read_sig = asm.sig(
+        [
+            Type::Int(Int::I32),
+            Type::Int(Int::USize),
+            Type::Int(Int::USize),
+        ],
+        Type::Int(Int::ISize),
+    );
+    let abs_sig = asm.sig([Type::Int(Int::I32)], Type::Int(Int::I32));
+    for (name, sig) in [("read", read_sig), ("abs", abs_sig)] {
+        let name = asm.alloc_string(name);
+        asm.alloc_methodref(MethodRef::new(
+            main_module,
+            name,
+            sig,
+            MethodKind::Static,
+            [].into(),
+        ));
+    }
+    let externs = [
+        ("read", "libc.so.6".to_owned()),
+        ("abs", "libc.so.6".to_owned()),
+    ]
+    .into_iter()
+    .collect();
+    let modifies_errno = ["read"].into_iter().collect();
+    asm.patch_missing_methods(&externs, &modifies_errno, &Default::default());
+    let pinvoke = |asm: &mut Assembly, name: &str| {
+        let name = asm.alloc_string(name);
+        let (_, def) = asm
+            .methods_with(|_, _, def| def.name() == name)
+            .next()
+            .unwrap();
+        pinvoke_attr(def.implementation(), asm)
+    };
+    // `read` reports failure trough errno, so the runtime must save it after the call.
+    assert_eq!(
+        pinvoke(&mut asm, "read"),
+        "pinvokeimpl(\"libc.so.6\" cdecl lasterr)"
+    );
+    assert_eq!(pinvoke(&mut asm, "abs"), "pinvokeimpl(\"libc.so.6\" cdecl)");
+    assert_eq!(pinvoke_attr(&MethodImpl::Missing, &asm), String::new());
+}
+#[test]
+fn simd_ld_st_ind() {
+    use super::{
+        cilnode::MethodKind, tpe::simd::SIMDVector, Access, BasicBlock, CILRoot, MethodImpl,
+    };
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let f32x4 = Type::SIMDVector(SIMDVector::new(super::Float::F32.into(), 4));
+    let f32x4_idx = asm.alloc_type(f32x4);
+    let f32x4_ptr = asm.nptr(f32x4);
+    let sig = asm.sig([f32x4_ptr, f32x4_ptr], Type::Void);
+    let mut roots = vec![];
+    // *dst = *src, both as plain and volatile accesses.
+    for volatile in [false, true] {
+        let src = asm.alloc_node(CILNode::LdArg(0));
+        let val = asm.alloc_node(CILNode::LdInd {
+            addr: src,
+            tpe: f32x4_idx,
+            volatile,
+        });
+        let dst = asm.alloc_node(CILNode::LdArg(1));
+        roots.push(asm.alloc_root(CILRoot::StInd(Box::new((dst, val, f32x4, volatile)))));
+    }
+    roots.push(asm.alloc_root(CILRoot::VoidRet));
+    for root in &roots {
+        asm[*root].clone().typecheck(sig, &[], &mut asm).unwrap();
+    }
+    let name = asm.alloc_string("copy_vec");
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(roots, 0, None)],
+            locals: vec![],
+        },
+        vec![None, None],
+    );
+    let il = method.dump_il(&asm);
+    // Vectors are loaded and stored as a whole, not lane by lane.
+    let vec_il = type_il(&f32x4, &asm);
+    assert!(il.contains(&format!("\nldobj {vec_il}")), "{il}");
+    assert!(il.contains(&format!("stobj {vec_il}")), "{il}");
+    assert!(il.contains(&format!("volatile. ldobj {vec_il}")), "{il}");
+    assert!(il.contains(&format!("volatile. stobj {vec_il}")), "{il}");
+}
+#[test]
+fn generic_call_instantiations() {
+    use super::{cilnode::MethodKind, Access, BasicBlock, CILRoot, MethodImpl, MethodRef};
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let name = asm.alloc_string("swap");
+    let swap_sig = asm.sig([], Type::Void);
+    let swap = |tpe: Type| {
+        MethodRef::new(
+            *main_module,
+            name,
+            swap_sig,
+            MethodKind::Static,
+            [tpe].into(),
+        )
+    };
+    let swap_i32 = asm.alloc_methodref(swap(Type::Int(Int::I32)));
+    let swap_f64 = asm.alloc_methodref(swap(Type::Float(super::Float::F64)));
+    // Different type arguments must result in distinct instantiations.
+    assert_ne!(swap_i32, swap_f64);
+    let call_i32 = asm.alloc_root(CILRoot::Call(Box::new((swap_i32, [].into()))));
+    let call_f64 = asm.alloc_root(CILRoot::Call(Box::new((swap_f64, [].into()))));
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let caller = asm.alloc_string("caller");
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        caller,
+        swap_sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![call_i32, call_f64, ret], 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    );
+    let il = method.dump_il(&asm);
+    assert!(il.contains("'swap'<int32>()"), "{il}");
+    assert!(il.contains("'swap'<float64>()"), "{il}");
+    // Translating a method reference into another assembly must preserve its generics.
+    let mut other = Assembly::default();
+    let translated = other.translate_method_ref(&asm, &asm[swap_f64]);
+    assert_eq!(translated.generics(), &[Type::Float(super::Float::F64)]);
+}
+#[test]
+fn ld_type_token_constructed() {
+    use super::{cilnode::MethodKind, Access, BasicBlock, CILRoot, ClassRef, MethodImpl};
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let list_name = asm.alloc_string("System.Collections.Generic.List");
+    let list_asm = Some(asm.alloc_string("System.Collections"));
+    let list_i32 = asm.alloc_class_ref(ClassRef::new(
+        list_name,
+        list_asm,
+        false,
+        [Type::Int(Int::I32)].into(),
+    ));
+    let i32_idx = asm.alloc_type(Type::Int(Int::I32));
+    let tokens: Vec<_> = [
+        Type::ClassRef(list_i32),
+        Type::PlatformArray {
+            elem: i32_idx,
+            dims: std::num::NonZeroU8::new(1).unwrap(),
+        },
+    ]
+    .into_iter()
+    .map(|tpe| {
+        let tpe = asm.alloc_type(tpe);
+        asm.alloc_node(CILNode::LdTypeToken(tpe))
+    })
+    .collect();
+    let ret = asm.alloc_root(CILRoot::VoidRet);
+    let mut roots: Vec<_> = tokens
+        .iter()
+        .map(|token| asm.alloc_root(CILRoot::Pop(*token)))
+        .collect();
+    roots.push(ret);
+    let sig = asm.sig([], Type::Void);
+    let name = asm.alloc_string("tokens");
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(roots, 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    );
+    let il = method.dump_il(&asm);
+    assert!(
+        il.contains("ldtoken class [System.Collections]'System.Collections.Generic.List`1'<int32>"),
+        "{il}"
+    );
+    assert!(il.contains("ldtoken int32[]"), "{il}");
+    // The fully constructed types must survive linking.
+    let mut other = Assembly::default();
+    for token in tokens {
+        let translated = other.translate_node(&asm, asm[token].clone());
+        let (CILNode::LdTypeToken(original), CILNode::LdTypeToken(translated)) =
+            (&asm[token], &translated)
+        else {
+            panic!("translate_node changed the node kind: {translated:?}");
+        };
+        assert_eq!(
+            type_il(&asm[*original], &asm),
+            type_il(&other[*translated], &other)
+        );
+    }
+}
+#[test]
+fn zero_size_loc_alloc() {
+    use super::{cilnode::MethodKind, Access, BasicBlock, CILRoot, MethodImpl};
+    let mut asm = Assembly::default();
+    let main_module = asm.main_module();
+    let size = asm.alloc_node(super::Const::USize(0));
+    let alloc = asm.alloc_node(CILNode::LocAlloc { size });
+    let ret = asm.alloc_root(CILRoot::Ret(alloc));
+    let void_ptr = asm.nptr(Type::Void);
+    let sig = asm.sig([], void_ptr);
+    let name = asm.alloc_string("alloca_zero");
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![],
+    );
+    let il = method.dump_il(&asm);
+    // The pointer is non-null and aligned, without ever reaching `localloc`.
+    assert!(!il.contains("localloc"), "{il}");
+    assert!(
+        il.contains(&format!("ldc.i4.s {ZERO_SIZE_LOCALLOC_ADDR} conv.u")),
+        "{il}"
+    );
+    assert!(ZERO_SIZE_LOCALLOC_ADDR.is_power_of_two());
+}
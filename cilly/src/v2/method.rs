@@ -5,8 +5,8 @@ use super::{
     basic_block::BlockId,
     bimap::{BiMapIndex, IntoBiMapIndex},
     cilnode::MethodKind,
-    Access, Assembly, BasicBlock, CILIterElem, CILNode, ClassDefIdx, ClassRef, ClassRefIdx, Int,
-    SigIdx, StringIdx, Type, TypeIdx,
+    Access, AliasCycle, Assembly, BasicBlock, CILIterElem, CILNode, ClassDefIdx, ClassRef,
+    ClassRefIdx, Int, SigIdx, StringIdx, Type, TypeIdx,
 };
 use crate::v2::iter::TpeIter;
 use crate::v2::CILRoot;
@@ -241,21 +241,40 @@ impl MethodDef {
     pub fn implementation(&self) -> &MethodImpl {
         &self.implementation
     }
+    /// Returns the implementation of this method, following any [`MethodImpl::AliasFor`].
+    /// # Panics
+    /// Panics if this method is part of an alias cycle. Use [`Self::try_resolved_implementation`] to handle that case.
     #[must_use]
     pub fn resolved_implementation<'asm: 'method, 'method>(
         &'method self,
         asm: &'asm Assembly,
     ) -> &'method MethodImpl {
+        self.try_resolved_implementation(asm)
+            .unwrap_or_else(|cycle| panic!("{cycle}"))
+    }
+    /// Returns the implementation of this method, following any [`MethodImpl::AliasFor`].
+    /// # Errors
+    /// Returns an [`AliasCycle`] if this method is part of an alias cycle.
+    pub fn try_resolved_implementation<'asm: 'method, 'method>(
+        &'method self,
+        asm: &'asm Assembly,
+    ) -> Result<&'method MethodImpl, AliasCycle> {
         match self.implementation {
             MethodImpl::MethodBody { .. } | MethodImpl::Extern { .. } | MethodImpl::Missing => {
-                &self.implementation
+                Ok(&self.implementation)
             }
-            MethodImpl::AliasFor(method) => asm
-                .method_def_from_ref(method)
+            MethodImpl::AliasFor(method) => Ok(asm
+                .method_def_from_ref(asm.resolve_alias(method)?)
                 .expect("ERROR: a method is an alias for an extern function")
-                .resolved_implementation(asm),
+                .implementation()),
         }
     }
+    /// Returns ilasm-like text representing the body of this method: its locals, blocks(with their labels) and instructions.
+    #[must_use]
+    pub fn dump_il(&self, asm: &Assembly) -> String {
+        crate::v2::il_exporter::ILExporter::new(crate::v2::asm::IlasmFlavour::Modern, false)
+            .dump_method(asm, self)
+    }
     pub fn implementation_mut(&mut self) -> &mut MethodImpl {
         &mut self.implementation
     }
@@ -871,3 +890,51 @@ fn cil() {
         None,
     );
 }
+#[test]
+fn dump_il() {
+    let mut asm = Assembly::default();
+    let name: StringIdx = asm.alloc_string("identity");
+    let arg = asm.alloc_node(CILNode::LdArg(0));
+    let ret = asm.alloc_root(CILRoot::Ret(arg));
+    let main_module = asm.main_module();
+    let sig = asm.sig([Type::Int(Int::I32)], Type::Int(Int::I32));
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::MethodBody {
+            blocks: vec![BasicBlock::new(vec![ret], 0, None)],
+            locals: vec![],
+        },
+        vec![None],
+    );
+    let il = method.dump_il(&asm);
+    assert!(il.contains("bb0:"), "{il}");
+    assert!(il.contains("ldarg"), "{il}");
+    assert!(il.contains("ret"), "{il}");
+}
+#[test]
+fn dump_il_missing() {
+    let mut asm = Assembly::default();
+    let name: StringIdx = asm.alloc_string("not_there");
+    let main_module = asm.main_module();
+    let sig = asm.sig([], Type::Int(Int::I32));
+    let method = MethodDef::new(
+        Access::Extern,
+        main_module,
+        name,
+        sig,
+        MethodKind::Static,
+        MethodImpl::Missing,
+        vec![],
+    );
+    let il = method.dump_il(&asm);
+    assert!(il.contains("missing method not_there"), "{il}");
+    assert!(
+        il.contains("System.NotImplementedException::.ctor(string)"),
+        "{il}"
+    );
+    assert!(il.trim_end().ends_with("throw"), "{il}");
+}
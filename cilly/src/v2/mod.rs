@@ -5,7 +5,7 @@
 use std::path::Path;
 
 pub use access::Access;
-pub use asm::{Assembly, IlasmFlavour};
+pub use asm::{AliasCycle, Assembly, AssemblyStats, IlasmFlavour};
 pub use basic_block::BasicBlock;
 pub use bimap::BiMap;
 pub use cilnode::{BinOp, CILNode, NodeIdx};
@@ -29,7 +29,7 @@ fn trivial_inline_block<'def, 'asm: 'def>(
     Some(block.meaningfull_roots(asm))
 }
 /// If the calle is nothing more than a handlerless block with one root, returning a value, then we can trivialy inline it. This is almost free, and should be always done when possible(excluding recursion).
-fn trivial_inline_node(
+pub(crate) fn trivial_inline_node(
     def: &MethodDef,
     call_args: &[NodeIdx],
     fuel: &mut OptFuel,
@@ -10,6 +10,7 @@ use super::{
     CILIter, CILIterElem, CILNode, CILRoot, Const, Int, MethodImpl, NodeIdx, RootIdx, SigIdx, Type,
 };
 use crate::v2::{Assembly, MethodDef};
+pub(crate) use inline::trivial_inline_node;
 pub use opt_fuel::OptFuel;
 pub use side_effect::*;
 mod inline;
@@ -43,42 +43,15 @@ impl Float {
     pub fn is_nan(&self, val: NodeIdx, asm: &mut Assembly) -> NodeIdx {
         let is_nan = asm.alloc_string("IsNaN");
         let sig = asm.sig([Type::Float(*self)], Type::Bool);
-        match self {
-            Float::F16 => {
-                let half = ClassRef::half(asm);
-                let mref = asm.alloc_methodref(MethodRef::new(
-                    half,
-                    is_nan,
-                    sig,
-                    MethodKind::Static,
-                    [].into(),
-                ));
-                asm.alloc_node(CILNode::Call(Box::new((mref, [val].into()))))
-            }
-            Float::F32 => {
-                let single = ClassRef::single(asm);
-                let mref = asm.alloc_methodref(MethodRef::new(
-                    single,
-                    is_nan,
-                    sig,
-                    MethodKind::Static,
-                    [].into(),
-                ));
-                asm.alloc_node(CILNode::Call(Box::new((mref, [val].into()))))
-            }
-            Float::F64 => {
-                let double = ClassRef::single(asm);
-                let mref = asm.alloc_methodref(MethodRef::new(
-                    double,
-                    is_nan,
-                    sig,
-                    MethodKind::Static,
-                    [].into(),
-                ));
-                asm.alloc_node(CILNode::Call(Box::new((mref, [val].into()))))
-            }
-            Float::F128 => todo!(),
-        }
+        let class = self.class(asm);
+        let mref = asm.alloc_methodref(MethodRef::new(
+            class,
+            is_nan,
+            sig,
+            MethodKind::Static,
+            [].into(),
+        ));
+        asm.alloc_node(CILNode::Call(Box::new((mref, [val].into()))))
     }
     /// Returns a short name of the float
     #[must_use]
@@ -116,6 +89,15 @@ impl Float {
             Float::F128 => todo!(),
         }
     }
+    /// Returns the class containing the math functions(`Sqrt`, `Floor`, etc.) operating on this floating-point type.
+    pub fn math_class(&self, asm: &mut Assembly) -> ClassRefIdx {
+        match self {
+            Float::F16 => ClassRef::half(asm),
+            Float::F32 => ClassRef::mathf(asm),
+            Float::F64 => ClassRef::math(asm),
+            Float::F128 => todo!(),
+        }
+    }
     /// Raises base to power.
     pub fn pow(&self, base: NodeIdx, exp: NodeIdx, asm: &mut Assembly) -> NodeIdx {
         let pow = asm.alloc_string("Pow");
@@ -180,3 +162,17 @@ fn name() {
     assert_eq!(Float::F64.name(), "f64");
     assert_eq!(Float::F128.name(), "f128");
 }
+#[test]
+fn class() {
+    let mut asm = Assembly::default();
+    for (float, class, math_class) in [
+        (Float::F16, "System.Half", "System.Half"),
+        (Float::F32, "System.Single", "System.MathF"),
+        (Float::F64, "System.Double", "System.Math"),
+    ] {
+        let cref = asm.float_class(float);
+        assert_eq!(&asm[asm[cref].name()], class);
+        let cref = asm.math_class(float);
+        assert_eq!(&asm[asm[cref].name()], math_class);
+    }
+}
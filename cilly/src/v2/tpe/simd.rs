@@ -44,11 +44,25 @@ impl SIMDVector {
         let res = Self { elem, count };
         let bits = res.bits();
         assert!(
-            bits == 64 || bits == 128 || bits == 256 || bits == 512,
+            Self::is_supported_size(bits),
             "A vec with {count} {elem:?} has the size of {bits}, which is not supported."
         );
         res
     }
+    /// Creates a new vector, if .NET has a vector type of this size.
+    /// ```
+    /// # use cilly::v2::tpe::simd::SIMDVector;
+    /// # use cilly::Int;
+    /// assert!(SIMDVector::try_new(Int::I32.into(), 4).is_some());
+    /// assert!(SIMDVector::try_new(Int::I32.into(), 3).is_none());
+    /// ```
+    pub fn try_new(elem: SIMDElem, count: u8) -> Option<Self> {
+        let res = Self { elem, count };
+        Self::is_supported_size(res.bits()).then_some(res)
+    }
+    fn is_supported_size(bits: u16) -> bool {
+        bits == 64 || bits == 128 || bits == 256 || bits == 512
+    }
     /// Returns a short name descirbing this vector.
     /// ```
     /// # use cilly::v2::tpe::simd::{SIMDElem,SIMDVector};
@@ -77,6 +91,23 @@ impl SIMDVector {
         self.elem.bits() as u16 * self.count() as u16
     }
 
+    /// Checks if .NET has a hardware vector type(`Vector64<T>` - `Vector512<T>`) for this vector. Those only support primitive
+    /// numeric elements, so vectors of eg. `u128` or `f16` don't have one.
+    /// ```
+    /// # use cilly::v2::tpe::simd::SIMDVector;
+    /// # use cilly::{Float,Int};
+    /// assert!(SIMDVector::new(Float::F32.into(), 4).has_dotnet_vector());
+    /// assert!(SIMDVector::new(Int::ISize.into(), 2).has_dotnet_vector());
+    /// assert!(!SIMDVector::new(Int::U128.into(), 2).has_dotnet_vector());
+    /// assert!(!SIMDVector::new(Float::F16.into(), 8).has_dotnet_vector());
+    /// ```
+    pub fn has_dotnet_vector(&self) -> bool {
+        let primitive_elem = match self.elem {
+            SIMDElem::Int(int) => !matches!(int, Int::I128 | Int::U128),
+            SIMDElem::Float(float) => matches!(float, Float::F32 | Float::F64),
+        };
+        primitive_elem && Self::is_supported_size(self.bits())
+    }
     pub fn elem(&self) -> SIMDElem {
         self.elem
     }
@@ -112,6 +112,11 @@ pub enum TypeCheckError {
     ConditionNotBool {
         cond: Type,
     },
+    /// The aligement of a `LocAllocAlgined` is not a power of two, or is lower than the natural aligement of its type.
+    LocAllocInvalidAlign {
+        align: u64,
+        natural_align: u64,
+    },
     CantCompareTypes {
         lhs: Type,
         rhs: Type,
@@ -610,26 +615,7 @@ impl CILNode {
                 let fn_ptr = asm.get_node(*fn_ptr).clone();
                 let fn_ptr = fn_ptr.typecheck(sig, locals, asm)?;
                 let called_sig = asm[*called_sig].clone();
-                if args.len() != called_sig.inputs().len() {
-                    return Err(TypeCheckError::IndirectCallArgcWrong {
-                        expected: called_sig.inputs().len(),
-                        got: args.len(),
-                    });
-                }
-
-                for (idx, (arg, input_type)) in
-                    args.iter().zip(called_sig.inputs().iter()).enumerate()
-                {
-                    let arg = asm.get_node(*arg).clone();
-                    let arg_type = arg.typecheck(sig, locals, asm)?;
-                    if !arg_type.is_assignable_to(*input_type, asm) {
-                        return Err(TypeCheckError::IndirectCallArgTypeWrong {
-                            got: arg_type,
-                            expected: *input_type,
-                            idx,
-                        });
-                    }
-                }
+                typecheck_calli_args(&called_sig, args, sig, locals, asm)?;
                 let Type::FnPtr(ptr_sig) = fn_ptr else {
                     return Err(TypeCheckError::IndirectCallInvalidFnPtrType { fn_ptr });
                 };
@@ -852,7 +838,16 @@ impl CILNode {
                 }
                 Ok(Type::Int(Int::I32))
             }
-            CILNode::LocAllocAlgined { tpe, align } => Ok(Type::Ptr(*tpe)),
+            CILNode::LocAllocAlgined { tpe, align } => {
+                let natural_align = asm.alignof_type(*tpe);
+                if !align.is_power_of_two() || *align < natural_align {
+                    return Err(TypeCheckError::LocAllocInvalidAlign {
+                        align: *align,
+                        natural_align,
+                    });
+                }
+                Ok(Type::Ptr(*tpe))
+            }
             CILNode::LdElelemRef { array, index } => {
                 let arr = asm.get_node(*array).clone();
                 let arr_tpe = arr.typecheck(sig, locals, asm)?;
@@ -890,6 +885,35 @@ impl CILNode {
         }
     }
 }
+/// Checks that the arguments of an indirect call match the inputs of the signature it is called with.
+/// # Errors
+/// Returns an error if the argument count is wrong, or one of the arguments can't be assigned to its input type.
+pub fn typecheck_calli_args(
+    called_sig: &super::FnSig,
+    args: &[NodeIdx],
+    sig: SigIdx,
+    locals: &[LocalDef],
+    asm: &mut Assembly,
+) -> Result<(), TypeCheckError> {
+    if args.len() != called_sig.inputs().len() {
+        return Err(TypeCheckError::IndirectCallArgcWrong {
+            expected: called_sig.inputs().len(),
+            got: args.len(),
+        });
+    }
+    for (idx, (arg, input_type)) in args.iter().zip(called_sig.inputs().iter()).enumerate() {
+        let arg = asm.get_node(*arg).clone();
+        let arg_type = arg.typecheck(sig, locals, asm)?;
+        if !arg_type.is_assignable_to(*input_type, asm) {
+            return Err(TypeCheckError::IndirectCallArgTypeWrong {
+                got: arg_type,
+                expected: *input_type,
+                idx,
+            });
+        }
+    }
+    Ok(())
+}
 impl CILRoot {
     pub fn typecheck(
         &self,
@@ -911,6 +935,12 @@ impl CILRoot {
                     Ok(())
                 }
             }
+            Self::CallI(info) => {
+                let (fn_ptr, called_sig, args) = info.as_ref();
+                asm.get_node(*fn_ptr).clone().typecheck(sig, locals, asm)?;
+                let called_sig = asm[*called_sig].clone();
+                typecheck_calli_args(&called_sig, args, sig, locals, asm)
+            }
             Self::Branch(boxed) => {
                 let (_, _, cond) = boxed.as_ref();
                 let Some(cond) = cond else { return Ok(()) };
@@ -1058,3 +1088,66 @@ fn test() {
     let _sum = asm.alloc_node(sum);
     let _sig = asm.sig([], Type::Void);
 }
+#[test]
+fn calli_argc() {
+    let mut asm = Assembly::default();
+    let sig = asm.sig([], Type::Void);
+    let called_sig = super::FnSig::new(
+        [Type::Int(Int::I32), Type::Int(Int::I32)].into(),
+        Type::Void,
+    );
+    let arg = asm.alloc_node(super::Const::I32(0));
+    assert!(matches!(
+        typecheck_calli_args(&called_sig, &[arg], sig, &[], &mut asm),
+        Err(TypeCheckError::IndirectCallArgcWrong {
+            expected: 2,
+            got: 1
+        })
+    ));
+    let float = asm.alloc_node(super::Const::F32(super::hashable::HashableF32(0.0)));
+    assert!(matches!(
+        typecheck_calli_args(&called_sig, &[arg, float], sig, &[], &mut asm),
+        Err(TypeCheckError::IndirectCallArgTypeWrong { idx: 1, .. })
+    ));
+    assert!(typecheck_calli_args(&called_sig, &[arg, arg], sig, &[], &mut asm).is_ok());
+}
+#[test]
+fn loc_alloc_align() {
+    let mut asm = Assembly::default();
+    let sig = asm.sig([], Type::Void);
+    let tpe = asm.alloc_type(Type::Int(Int::I32));
+    let aligned = |align| CILNode::LocAllocAlgined { tpe, align };
+    assert!(aligned(16).typecheck(sig, &[], &mut asm).is_ok());
+    assert!(aligned(4).typecheck(sig, &[], &mut asm).is_ok());
+    // Not a power of two
+    assert!(matches!(
+        aligned(3).typecheck(sig, &[], &mut asm),
+        Err(TypeCheckError::LocAllocInvalidAlign {
+            align: 3,
+            natural_align: 4
+        })
+    ));
+    // Lower than the natural aligement of `i32`
+    assert!(matches!(
+        aligned(2).typecheck(sig, &[], &mut asm),
+        Err(TypeCheckError::LocAllocInvalidAlign { align: 2, .. })
+    ));
+}
+#[test]
+fn fn_ptr_casts() {
+    let mut asm = Assembly::default();
+    let fn_sig = asm.sig([], Type::Int(Int::I32));
+    let sig = asm.sig([Type::FnPtr(fn_sig), Type::Int(Int::I64)], Type::Void);
+    // A function pointer can be cast to `usize`, and back.
+    let fn_ptr = asm.alloc_node(CILNode::LdArg(0));
+    let addr = asm.alloc_node(CILNode::PtrCast(fn_ptr, Box::new(PtrCastRes::USize)));
+    let back = CILNode::PtrCast(addr, Box::new(PtrCastRes::FnPtr(fn_sig)));
+    assert!(matches!(back.typecheck(sig, &[], &mut asm), Ok(Type::FnPtr(res)) if res == fn_sig));
+    // `i64` is not pointer-sized, so it can't be cast to a function pointer.
+    let int = asm.alloc_node(CILNode::LdArg(1));
+    let invalid = CILNode::PtrCast(int, Box::new(PtrCastRes::FnPtr(fn_sig)));
+    assert!(matches!(
+        invalid.typecheck(sig, &[], &mut asm),
+        Err(TypeCheckError::InvalidPtrCast { .. })
+    ));
+}
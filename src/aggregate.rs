@@ -265,6 +265,30 @@ fn aggregate_adt<'tcx>(
     active_field: Option<FieldIdx>,
 ) -> (Vec<CILRoot>, CILNode) {
     let adt_type = ctx.monomorphize(adt_type);
+    // SIMD vectors(and single-lane vectors, which are just their element) are not classes. Their only field is the array of their lanes, which has the
+    // same layout as the vector itself, so the vector gets initialized by reinterpreting it as that array.
+    if adt.repr().simd() && get_type(adt_type, ctx).as_class_ref().is_none() {
+        let [(_, lanes)]: [(u32, CILNode); 1] = fields
+            .try_into()
+            .unwrap_or_else(|_| panic!("SIMD vector {adt_type:?} must have exactly 1 field"));
+        let array_ty = adt
+            .all_fields()
+            .next()
+            .expect("SIMD vectors must have a field")
+            .ty(ctx.tcx(), subst);
+        let array_ty = ctx.monomorphize(array_ty);
+        let array_tpe = ctx.type_from_cache(array_ty);
+        let addr = crate::place::place_adress(target_location, ctx).cast_ptr(ctx.nptr(array_tpe));
+        return (
+            vec![crate::place::ptr_set_op(
+                crate::place::PlaceTy::Ty(array_ty),
+                ctx,
+                addr,
+                lanes,
+            )],
+            place_get(target_location, ctx),
+        );
+    }
     let adt_type_ref = get_type(adt_type, ctx)
         .as_class_ref()
         .unwrap_or_else(|| panic!("Type {adt_type:?} is not a valuetype."));
@@ -805,26 +805,5 @@ pub fn alloc_buff_tpe(asm: &mut cilly::v2::Assembly, len: u64) -> Option<Type> {
     }
 }*/
 pub fn add_const_value(asm: &mut cilly::v2::Assembly, bytes: u128) -> StaticFieldDesc {
-    let uint8_ptr = Type::Int(Int::U128);
-    let main_module_id = asm.main_module();
-    let alloc_fld: IString = format!("a_{bytes:x}").into();
-    let alloc_fld_name = asm.alloc_string(alloc_fld.clone());
-
-    let field_desc = StaticFieldDesc::new(*asm.main_module(), alloc_fld_name, Type::Int(Int::U128));
-
-    let main_module = asm.class_mut(main_module_id);
-    if main_module.has_static_field(alloc_fld_name, field_desc.tpe()) {
-        return field_desc;
-    }
-    asm.add_static(uint8_ptr, alloc_fld, false, main_module_id);
-    let cst = CILNode::const_u128(bytes, asm);
-
-    let field = asm.alloc_sfld(field_desc);
-    let val = cilly::v2::CILNode::from_v1(&cst, asm);
-    let val = asm.alloc_node(val);
-    let set = asm.alloc_root(cilly::v2::CILRoot::SetStaticField { field, val });
-
-    asm.add_cctor(&[set]);
-
-    field_desc
+    asm.const_value_static(bytes)
 }
@@ -7,29 +7,71 @@ use cilly::{
     call, conv_f32, conv_f64, conv_f_un, conv_i16, conv_i32, conv_i64, conv_i8, conv_isize,
     conv_u16, conv_u32, conv_u64, conv_u8, conv_usize,
 };
+/// Casts from intiger type `src` to target `target`, throwing an exception if the value of the integer changes.
+/// Falls back to an unchecked cast for types the checks don't support(`bool`, 128 bit ints).
+pub fn checked_int_to_int(
+    src: Type,
+    target: Type,
+    operand: CILNode,
+    asm: &mut Assembly,
+) -> CILNode {
+    match (src, target) {
+        (Type::Int(src_int), Type::Int(target_int))
+            if src != target
+                && !matches!(src_int, Int::I128 | Int::U128)
+                && !matches!(target_int, Int::I128 | Int::U128) =>
+        {
+            let mref = MethodRef::new(
+                *asm.main_module(),
+                asm.alloc_string(format!(
+                    "checked_cast_{}_{}",
+                    src_int.name(),
+                    target_int.name()
+                )),
+                asm.sig([src], target),
+                MethodKind::Static,
+                vec![].into(),
+            );
+            call!(asm.alloc_methodref(mref), [operand])
+        }
+        _ => int_to_int(src, target, operand, asm),
+    }
+}
 /// Casts from intiger type `src` to target `target`
 pub fn int_to_int(src: Type, target: Type, operand: CILNode, asm: &mut Assembly) -> CILNode {
     if src == target {
         return operand;
     }
     match (&src, &target) {
-        // Unsinged casts are special
+        // Unsinged casts are special. `bool` is unsigned too, so it must be zero-extended.
         (
-            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize),
+            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize) | Type::Bool,
             Type::Int(Int::ISize),
         ) => {
             conv_isize!(conv_usize!(operand))
         }
-        (Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize), Type::Int(Int::I64)) => {
+        (
+            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize) | Type::Bool,
+            Type::Int(Int::I64),
+        ) => {
             conv_i64!(conv_u64!(operand))
         }
-        (Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize), Type::Int(Int::I32)) => {
+        (
+            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize) | Type::Bool,
+            Type::Int(Int::I32),
+        ) => {
             conv_i32!(conv_u32!(operand))
         }
-        (Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize), Type::Int(Int::I16)) => {
+        (
+            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize) | Type::Bool,
+            Type::Int(Int::I16),
+        ) => {
             conv_i16!(conv_u16!(operand))
         }
-        (Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize), Type::Int(Int::I8)) => {
+        (
+            Type::Int(Int::U32 | Int::U16 | Int::U8 | Int::U64 | Int::USize) | Type::Bool,
+            Type::Int(Int::I8),
+        ) => {
             conv_i8!(conv_u8!(operand))
         }
         //
@@ -710,6 +710,187 @@ fn with_stack_size(cmd: &mut std::process::Command, limit_kb: u64) {
 fn backend_path() -> String {
     format!("codegen-backend={}", absolute_backend_path().display())
 }
+#[test]
+fn catch_unwind_panic_abort() {
+    let test_dir = "./test/intrinsics/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .args(rustc_args().iter())
+        .args(["-C", "panic=abort", "./catch.rs", "-o", "./catch_abort.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    if *crate::config::DRY_RUN {
+        return;
+    }
+    // Panics can't be caught under `panic=abort`, so no method should get an exception handler.
+    let asm: cilly::v2::Assembly = postcard::from_bytes(
+        &std::fs::read(format!("{test_dir}catch_abort.cilly2"))
+            .expect("Could not read the linked assembly"),
+    )
+    .expect("Could not decode the linked assembly");
+    for (_, def) in asm.method_defs() {
+        let Some(blocks) = def.blocks(&asm) else {
+            continue;
+        };
+        assert!(
+            blocks.iter().all(|block| block.handler().is_none()),
+            "{} was compiled with an exception handler under panic=abort:\n{}",
+            &asm[def.name()],
+            def.dump_il(&asm)
+        );
+    }
+    let stdout = test_dotnet_executable("./catch_abort", test_dir);
+    assert!(
+        !stdout.contains("Closure panicked"),
+        "catch_unwind reported a panic under panic=abort: {stdout}"
+    );
+}
+#[test]
+fn simd_inlined_helpers() {
+    let test_dir = "./test/intrinsics/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .env("INLINE_SIMD_HELPERS", "1")
+        .args(rustc_args().iter())
+        .args(["./simd.rs", "-o", "./simd_inlined.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    // The SIMD tests(eg. `simd_add`) must produce the same results with the helpers inlined.
+    let _ = test_dotnet_executable("./simd_inlined", test_dir);
+}
+#[test]
+fn swap_large_localloc_cap() {
+    let test_dir = "./test/std/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .env("LOCALLOC_CAP", "65536")
+        .args(rustc_args().iter())
+        .args(["./swap_large.rs", "-o", "./swap_large.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    // With `LOCALLOC_CAP` set, swapping big values must not overflow the stack.
+    let _ = test_dotnet_executable("./swap_large", test_dir);
+}
+#[test]
+fn checked_int_casts() {
+    let test_dir = "./test/cast/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .env("CHECKED_INT_CASTS", "1")
+        .args(rustc_args().iter())
+        .args(["./checked_cast.rs", "-o", "./checked_cast.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    if *crate::config::DRY_RUN {
+        return;
+    }
+    // The out-of-range `i64 as i32` cast must throw.
+    let res = std::panic::catch_unwind(|| test_dotnet_executable("./checked_cast", test_dir));
+    let err = res.expect_err("A truncating cast did not throw with CHECKED_INT_CASTS");
+    let msg = err
+        .downcast_ref::<String>()
+        .expect("test_dotnet_executable panicked with a non-string payload");
+    assert!(
+        msg.contains("Checked cast: a i64 can't be cast to a i32"),
+        "A truncating cast failed with an unexpected error: {msg}"
+    );
+}
+#[test]
+fn gather_misaligned_ptr() {
+    let test_dir = "./test/intrinsics/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .env("SIMD_PTR_CHECKS", "1")
+        .args(rustc_args().iter())
+        .args(["./gather_misaligned.rs", "-o", "./gather_misaligned.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    if *crate::config::DRY_RUN {
+        return;
+    }
+    // The misaligned pointer of an active lane must trap.
+    let res = std::panic::catch_unwind(|| test_dotnet_executable("./gather_misaligned", test_dir));
+    let err = res.expect_err("A gather trough a misaligned pointer did not throw");
+    let msg = err
+        .downcast_ref::<String>()
+        .expect("test_dotnet_executable panicked with a non-string payload");
+    assert!(
+        msg.contains("simd_gather: the pointer of lane 2 is null or misaligned."),
+        "A gather trough a misaligned pointer failed with an unexpected error: {msg}"
+    );
+}
+#[test]
+fn missing_method_stub() {
+    let test_dir = "./test/";
+    RUSTC_BUILD_STATUS.as_ref().expect("Could not build rustc!");
+    let out = std::process::Command::new("rustc")
+        .current_dir(test_dir)
+        .args(rustc_args().iter())
+        .args(["./missing_method.rs", "-o", "./missing_method.exe"])
+        .output()
+        .expect("failed to execute process");
+    if !out.stderr.is_empty() {
+        let stdout =
+            String::from_utf8(out.stdout).expect("rustc error contained non-UTF8 characters.");
+        let stderr =
+            String::from_utf8(out.stderr).expect("rustc error contained non-UTF8 characters.");
+        panic!("stdout:\n{stdout}\nstderr:\n{stderr}");
+    }
+    if *crate::config::DRY_RUN {
+        return;
+    }
+    // Calling a method no library defines must reach its stub, which throws.
+    let res = std::panic::catch_unwind(|| test_dotnet_executable("./missing_method", test_dir));
+    let err = res.expect_err("Calling a missing method did not throw");
+    let msg = err
+        .downcast_ref::<String>()
+        .expect("test_dotnet_executable panicked with a non-string payload");
+    assert!(
+        msg.contains("System.NotImplementedException")
+            && msg.contains("missing method rcl_missing_method_stub"),
+        "Calling a missing method failed with an unexpected error: {msg}"
+    );
+}
 test_lib! {assign,stable}
 test_lib! {binops,stable}
 test_lib! {branches,stable}
@@ -740,11 +921,13 @@ run_test! {arthm,not,stable}
 run_test! {arthm,num_test,stable}
 run_test! {arthm,shift,stable}
 run_test! {arthm,sub,stable}
+run_test! {arthm,total_cmp,stable}
 run_test! {arthm,xor,stable}
 run_test! {cast,i8_to_u64,stable}
 run_test! {cast,i16_to_u64,stable}
 run_test! {cast,i32_to_u64,stable}
 run_test! {cast,i32_to_usize,stable}
+run_test! {cast,bool_char,stable}
 run_test! {cast,coerce_unsized,unstable}
 run_test! {control_flow,cf_for,stable}
 run_test! {control_flow,drop,stable}
@@ -763,15 +946,22 @@ run_test! {intrinsics,catch,stable}
 run_test! {intrinsics,cmp_bytes,stable}
 run_test! {intrinsics,copy_nonoverlaping,stable}
 run_test! {intrinsics,ctpop,stable}
+run_test! {intrinsics,dangling,stable}
+run_test! {intrinsics,exp_log,stable}
 run_test! {intrinsics,malloc,stable}
+run_test! {intrinsics,min_max,stable}
+run_test! {intrinsics,nested_catch,stable}
 run_test! {intrinsics,offset_of,unstable}
 run_test! {intrinsics,overflow_ops,stable}
 run_test! {intrinsics,pow_sqrt,stable}
 run_test! {intrinsics,printf,stable}
+run_test! {intrinsics,ptr_offset_from,stable}
 run_test! {intrinsics,ptr_offset_from_unsigned,stable}
 run_test! {intrinsics,round,stable}
+run_test! {intrinsics,sign_bits,stable}
 run_test! {intrinsics,simd,stable}
 run_test! {intrinsics,size_of_val,stable}
+run_test! {intrinsics,statically_known,stable}
 run_test! {intrinsics,transmute,stable}
 run_test! {intrinsics,trigonometry,stable}
 run_test! {intrinsics,type_id,stable}
@@ -780,6 +970,7 @@ run_test! {iter,fold,stable}
 run_test! {statics,thread_local,stable}
 run_test! {std,arg_test,stable}
 run_test! {std,const_error,stable}
+run_test! {std,errno,stable}
 run_test! {std,cell_test,unstable}
 run_test! {std,cstr,unstable}
 run_test! {std,format,unstable}
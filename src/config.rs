@@ -70,6 +70,10 @@ config_flag! {PRINT_LOCAL_TYPES,false,"Prints local types of all compiled MIR fu
 config_flag! {VALIDTE_VALUES,false,"Tells the codegen to insert additional checks on each variable asigement."}
 config_flag! {OPTIMIZE_CIL,true,"Tells the codegen to optmize the emiited CIL."}
 
+config_flag! {FLOAT_BIT_OPS,true,"Tells the codegen to lower `fabs` and `copysign` to bit operations on the bits of a float, instead of calls to .NET math functions."}
+
+config_flag! {CHECKED_INT_CASTS,false,"Tells the codegen to check that integer `as` casts don't lose information(eg. by truncation or a sign change), and throw an exception if they do. Meant for debugging UB."}
+
 config_flag! {NEW_UNSIZE,false,"Turns out the new unsizing code"}
 
 config_flag! {ESCAPE_NAMES,false,"ells the codegen to escape class and method names."}
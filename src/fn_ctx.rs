@@ -1,5 +1,6 @@
 use crate::r#type::get_type;
-use cilly::v2::Assembly;
+use cilly::cil_node::{CILNode, CallOpArgs};
+use cilly::v2::{cilnode::MethodKind, Assembly, ClassRefIdx, MethodRef};
 use cilly::Type;
 use rustc_middle::ty::layout::HasTypingEnv;
 use rustc_middle::ty::{Instance, PseudoCanonicalInput, TyCtxt};
@@ -100,6 +101,26 @@ impl<'tcx, 'asm> MethodCompileCtx<'tcx, 'asm> {
             .expect("Could not get type layout!")
     }
 
+    /// Calls the static math method `class::method` with `args`. All the arguments are of the return type `ret`, which is the case for most float intrinsics.
+    pub fn call_math(
+        &mut self,
+        class: ClassRefIdx,
+        method: &str,
+        args: impl Into<Box<[CILNode]>>,
+        ret: Type,
+    ) -> CILNode {
+        let args = args.into();
+        let sig = self.sig(vec![ret; args.len()], ret);
+        let method = self.alloc_string(method);
+        let site = self.alloc_methodref(MethodRef::new(
+            class,
+            method,
+            sig,
+            MethodKind::Static,
+            vec![].into(),
+        ));
+        CILNode::Call(Box::new(CallOpArgs { args, site }))
+    }
     pub fn asm_mut<'s: 'a, 'a>(&'s mut self) -> &'a mut Assembly {
         self.asm
     }
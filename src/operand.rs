@@ -134,3 +134,61 @@ pub(crate) fn is_const_zero<'tcx>(
         }
     }
 }
+/// Returns the value of `operand`, if it is a signed integer constant.
+pub(crate) fn const_int<'tcx>(
+    operand: &Operand<'tcx>,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> Option<i128> {
+    let Operand::Constant(const_val) = operand else {
+        return None;
+    };
+    let constant = ctx.monomorphize(const_val.const_);
+    let evaluated = constant
+        .eval(
+            ctx.tcx(),
+            rustc_middle::ty::TypingEnv::fully_monomorphized(),
+            const_val.span,
+        )
+        .expect("Could not evaluate constant!");
+    match evaluated {
+        ConstValue::Scalar(Scalar::Int(int)) => Some(int.to_int(int.size())),
+        _ => None,
+    }
+}
+/// Returns the bytes of a constant operand, if it is a constant that could be evaluated to plain data.
+pub(crate) fn const_bytes<'tcx>(
+    operand: &Operand<'tcx>,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> Option<Vec<u8>> {
+    let Operand::Constant(const_val) = operand else {
+        return None;
+    };
+    let constant = ctx.monomorphize(const_val.const_);
+    let size = ctx.layout_of(constant.ty()).size;
+    let evaluated = constant
+        .eval(
+            ctx.tcx(),
+            rustc_middle::ty::TypingEnv::fully_monomorphized(),
+            const_val.span,
+        )
+        .expect("Could not evaluate constant!");
+    match evaluated {
+        ConstValue::Scalar(Scalar::Int(int)) => {
+            Some(int.to_bits(size).to_le_bytes()[..size.bytes_usize()].to_vec())
+        }
+        ConstValue::Indirect { alloc_id, offset } => {
+            let rustc_middle::mir::interpret::GlobalAlloc::Memory(data) =
+                ctx.tcx().global_alloc(alloc_id)
+            else {
+                return None;
+            };
+            let range = offset.bytes_usize()..(offset + size).bytes_usize();
+            Some(
+                data.inner()
+                    .inspect_with_uninit_and_ptr_outside_interpreter(range)
+                    .to_vec(),
+            )
+        }
+        _ => None,
+    }
+}
@@ -107,6 +107,14 @@ fn field_address<'a>(
                 crate::r#type::pointer_to_is_fat(curr_type, ctx.tcx(), ctx.instance()),
                 crate::r#type::pointer_to_is_fat(field_ty, ctx.tcx(), ctx.instance()),
             ) {
+                // The only field of a SIMD vector is the array of its lanes, which is at its very start.
+                (false, false)
+                    if curr_type.is_simd()
+                        && ctx.type_from_cache(curr_type).as_class_ref().is_none() =>
+                {
+                    let field_type = ctx.type_from_cache(field_ty);
+                    addr_calc.cast_ptr(ctx.nptr(field_type))
+                }
                 (false, false) => {
                     let field_desc = crate::utilis::field_descrptor(curr_type, field_index, ctx);
                 CILNode::LDFieldAdress {
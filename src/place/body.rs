@@ -39,6 +39,19 @@ fn body_field<'a>(
                 crate::r#type::pointer_to_is_fat(curr_type, ctx.tcx(), ctx.instance()),
                 crate::r#type::pointer_to_is_fat(field_type, ctx.tcx(), ctx.instance()),
             ) {
+                // SIMD vectors have no .NET fields, so their lanes are accessed trough a pointer to their start.
+                (false, false)
+                    if curr_type.is_simd()
+                        && ctx.type_from_cache(curr_type).as_class_ref().is_none() =>
+                {
+                    let field_tpe = ctx.type_from_cache(field_type);
+                    let addr = parrent_node.cast_ptr(ctx.nptr(field_tpe));
+                    if body_ty_is_by_adress(field_type, ctx) {
+                        (field_type.into(), addr)
+                    } else {
+                        (field_type.into(), super::deref_op(field_type.into(), ctx, addr))
+                    }
+                }
                 (false, false) => {
                     let field_desc = crate::utilis::field_descrptor(curr_type, field_index, ctx);
                     if body_ty_is_by_adress(field_type, ctx) {
@@ -78,6 +78,15 @@ fn get_field<'a>(
                 crate::r#type::pointer_to_is_fat(curr_type, ctx.tcx(), ctx.instance()),
                 crate::r#type::pointer_to_is_fat(field_type, ctx.tcx(), ctx.instance()),
             ) {
+                // SIMD vectors are not classes: their lanes are read trough a pointer to their start.
+                (false, false)
+                    if curr_type.is_simd()
+                        && ctx.type_from_cache(curr_type).as_class_ref().is_none() =>
+                {
+                    let field_tpe = ctx.type_from_cache(field_type);
+                    let addr = addr_calc.cast_ptr(ctx.nptr(field_tpe));
+                    super::deref_op(field_type.into(), ctx, addr)
+                }
                 (false, false) => {
                     let field_desc = crate::utilis::field_descrptor(curr_type, field_index, ctx);
                     CILNode::LDField {
@@ -84,6 +84,10 @@ pub fn handle_rvalue<'tcx>(
             crate::binop::binop(*binop, &operands.0, &operands.1, ctx),
         ),
         Rvalue::UnaryOp(binop, operand) => (vec![], crate::unop::unop(*binop, operand, ctx)),
+        Rvalue::Cast(CastKind::IntToInt, operand, target) if *crate::config::CHECKED_INT_CASTS => (
+            vec![],
+            cast!(ctx, operand, target, crate::casts::checked_int_to_int, ctx),
+        ),
         Rvalue::Cast(CastKind::IntToInt, operand, target) => (
             vec![],
             cast!(ctx, operand, target, crate::casts::int_to_int, ctx),
@@ -188,18 +192,8 @@ pub fn handle_rvalue<'tcx>(
             let src = operand.ty(&ctx.body().local_decls, ctx.tcx());
             let src = ctx.monomorphize(src);
             let src = ctx.type_from_cache(src);
-            match (&src, &dst) {
-                (
-                    Type::Int(Int::ISize | Int::USize) | Type::Ptr(_) | Type::FnPtr(_),
-                    Type::Int(Int::ISize | Int::USize) | Type::Ptr(_) | Type::FnPtr(_),
-                ) => (vec![], handle_operand(operand, ctx).cast_ptr(dst)),
-
-                (Type::Int(Int::U16), Type::PlatformChar) => (vec![], handle_operand(operand, ctx)),
-                (_, _) => (
-                    vec![],
-                    handle_operand(operand, ctx).transmute_on_stack(src, dst, ctx),
-                ),
-            }
+            let val = handle_operand(operand, ctx);
+            (vec![], crate::transmute::transmute(src, dst, val, ctx))
         }
         Rvalue::ShallowInitBox(operand, dst) => {
             let dst = ctx.monomorphize(*dst);
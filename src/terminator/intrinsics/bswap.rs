@@ -24,6 +24,12 @@ pub fn bswap<'tcx>(
     let ty = args[0].node.ty(ctx.body(), ctx.tcx());
     let ty = ctx.monomorphize(ty);
     let tpe = ctx.type_from_cache(ty);
+    // `bswap` never changes the width of its argument.
+    debug_assert_eq!(
+        ctx.type_from_cache(ctx.monomorphize(destination.ty(ctx.body(), ctx.tcx()).ty)),
+        tpe,
+        "The result of `bswap` must have the same type as its argument!"
+    );
     let operand = handle_operand(&args[0].node, ctx);
     place_set(
         destination,
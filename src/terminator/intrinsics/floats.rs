@@ -1,10 +1,15 @@
-use crate::{assembly::MethodCompileCtx, operand::handle_operand, place::place_set};
+use crate::{
+    assembly::MethodCompileCtx,
+    operand::{const_int, handle_operand},
+    place::place_set,
+};
 use cilly::{
     call,
     cil_node::CILNode,
     cil_root::CILRoot,
     cilnode::MethodKind,
     conv_f32, conv_f64,
+    hashable::{HashableF32, HashableF64},
     v2::{ClassRef, Float},
     Int, MethodRef, Type,
 };
@@ -13,6 +18,88 @@ use rustc_middle::{
     ty::Instance,
 };
 use rustc_span::source_map::Spanned;
+/// Reinterprets the float `val` as an unsigned integer of the same size.
+fn float_to_bits(val: CILNode, float: Float, ctx: &mut MethodCompileCtx<'_, '_>) -> CILNode {
+    let (name, bits) = match float {
+        Float::F32 => ("SingleToUInt32Bits", Int::U32),
+        Float::F64 => ("DoubleToUInt64Bits", Int::U64),
+        _ => todo!("Can't get the bits of a {float:?}"),
+    };
+    let bit_converter = ClassRef::bit_converter(ctx);
+    let sig = ctx.sig([Type::Float(float)], Type::Int(bits));
+    let to_bits = ctx.new_methodref(bit_converter, name, sig, MethodKind::Static, []);
+    call!(to_bits, [val])
+}
+/// Reinterprets the unsigned integer `bits` as a float of the same size.
+fn float_from_bits(bits: CILNode, float: Float, ctx: &mut MethodCompileCtx<'_, '_>) -> CILNode {
+    let (name, int) = match float {
+        Float::F32 => ("UInt32BitsToSingle", Int::U32),
+        Float::F64 => ("UInt64BitsToDouble", Int::U64),
+        _ => todo!("Can't create a {float:?} from bits"),
+    };
+    let bit_converter = ClassRef::bit_converter(ctx);
+    let sig = ctx.sig([Type::Int(int)], Type::Float(float));
+    let from_bits = ctx.new_methodref(bit_converter, name, sig, MethodKind::Static, []);
+    call!(from_bits, [bits])
+}
+/// Returns a mask with all bits but the sign bit of `float` set.
+fn float_abs_mask(float: Float, ctx: &mut MethodCompileCtx<'_, '_>) -> CILNode {
+    match float {
+        Float::F32 => CILNode::V2(ctx.alloc_node(u32::MAX >> 1)),
+        Float::F64 => CILNode::V2(ctx.alloc_node(u64::MAX >> 1)),
+        _ => todo!("Can't get the sign mask of a {float:?}"),
+    }
+}
+/// Returns a mask with only the sign bit of `float` set.
+fn float_sign_mask(float: Float, ctx: &mut MethodCompileCtx<'_, '_>) -> CILNode {
+    match float {
+        Float::F32 => CILNode::V2(ctx.alloc_node(!(u32::MAX >> 1))),
+        Float::F64 => CILNode::V2(ctx.alloc_node(!(u64::MAX >> 1))),
+        _ => todo!("Can't get the sign mask of a {float:?}"),
+    }
+}
+/// Implementation of the fabsf32 and fabsf64 intrinsics. Clears the sign bit of the float, so the sign of NaNs is cleared too.
+pub fn fabs<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    float: Float,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    let val = handle_operand(&args[0].node, ctx);
+    if !*crate::config::FLOAT_BIT_OPS {
+        let class = ctx.float_class(float);
+        let abs = ctx.call_math(class, "Abs", [val], Type::Float(float));
+        return place_set(destination, abs, ctx);
+    }
+    let bits = float_to_bits(val, float, ctx);
+    let mask = float_abs_mask(float, ctx);
+    let abs = float_from_bits(CILNode::And(Box::new(bits), Box::new(mask)), float, ctx);
+    place_set(destination, abs, ctx)
+}
+/// Implementation of the copysignf32 and copysignf64 intrinsics. Returns the magnitude of the first argument with the sign bit of the second one,
+/// so signed zeroes and NaNs are handled too.
+pub fn copysign<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    float: Float,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    let magnitude = handle_operand(&args[0].node, ctx);
+    let sign = handle_operand(&args[1].node, ctx);
+    if !*crate::config::FLOAT_BIT_OPS {
+        let class = ctx.float_class(float);
+        let copy_sign = ctx.call_math(class, "CopySign", [magnitude, sign], Type::Float(float));
+        return place_set(destination, copy_sign, ctx);
+    }
+    let magnitude = float_to_bits(magnitude, float, ctx);
+    let abs_mask = float_abs_mask(float, ctx);
+    let magnitude = CILNode::And(Box::new(magnitude), Box::new(abs_mask));
+    let sign = float_to_bits(sign, float, ctx);
+    let sign_mask = float_sign_mask(float, ctx);
+    let sign = CILNode::And(Box::new(sign), Box::new(sign_mask));
+    let copy_sign = float_from_bits(CILNode::Or(Box::new(magnitude), Box::new(sign)), float, ctx);
+    place_set(destination, copy_sign, ctx)
+}
 /// Implementation of the fmaf32 intrinsics. Takes in 3 arguments: a, b, c. Calcualtes a * b + c
 pub fn fmaf32<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
@@ -20,27 +107,15 @@ pub fn fmaf32<'tcx>(
     call_instance: Instance<'tcx>,
     ctx: &mut MethodCompileCtx<'tcx, '_>,
 ) -> CILRoot {
-    let mref = MethodRef::new(
-        ClassRef::single(ctx),
-        ctx.alloc_string("FusedMultiplyAdd"),
-        ctx.sig(
-            [
-                Type::Float(Float::F32),
-                Type::Float(Float::F32),
-                Type::Float(Float::F32),
-            ],
-            Type::Float(Float::F32),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-    let value_calc = call!(
-        ctx.alloc_methodref(mref),
-        [
-            handle_operand(&args[0].node, ctx),
-            handle_operand(&args[1].node, ctx),
-            handle_operand(&args[2].node, ctx),
-        ]
+    let class = ctx.float_class(Float::F32);
+    let a = handle_operand(&args[0].node, ctx);
+    let b = handle_operand(&args[1].node, ctx);
+    let c = handle_operand(&args[2].node, ctx);
+    let value_calc = ctx.call_math(
+        class,
+        "FusedMultiplyAdd",
+        [a, b, c],
+        Type::Float(Float::F32),
     );
     place_set(destination, value_calc, ctx)
 }
@@ -51,95 +126,99 @@ pub fn fmaf64<'tcx>(
     call_instance: Instance<'tcx>,
     ctx: &mut MethodCompileCtx<'tcx, '_>,
 ) -> CILRoot {
-    let mref = MethodRef::new(
-        ClassRef::double(ctx),
-        ctx.alloc_string("FusedMultiplyAdd"),
-        ctx.sig(
-            [
-                Type::Float(Float::F64),
-                Type::Float(Float::F64),
-                Type::Float(Float::F64),
-            ],
-            Type::Float(Float::F64),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-    let value_calc = call!(
-        ctx.alloc_methodref(mref),
-        [
-            handle_operand(&args[0].node, ctx),
-            handle_operand(&args[1].node, ctx),
-            handle_operand(&args[2].node, ctx),
-        ]
+    let class = ctx.float_class(Float::F64);
+    let a = handle_operand(&args[0].node, ctx);
+    let b = handle_operand(&args[1].node, ctx);
+    let c = handle_operand(&args[2].node, ctx);
+    let value_calc = ctx.call_math(
+        class,
+        "FusedMultiplyAdd",
+        [a, b, c],
+        Type::Float(Float::F64),
     );
     place_set(destination, value_calc, ctx)
 }
-pub fn powif32<'tcx>(
-    args: &[Spanned<Operand<'tcx>>],
-    destination: &Place<'tcx>,
-    call_instance: Instance<'tcx>,
-    ctx: &mut MethodCompileCtx<'tcx, '_>,
-) -> CILRoot {
-    debug_assert_eq!(
-        args.len(),
-        2,
-        "The intrinsic `powif32` MUST take in exactly 2 arguments!"
-    );
-    let pow = MethodRef::new(
-        ClassRef::single(ctx),
-        ctx.alloc_string("Pow"),
-        ctx.sig(
-            [Type::Float(Float::F32), Type::Float(Float::F32)],
-            Type::Float(Float::F32),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-    place_set(
-        destination,
-        call!(
-            ctx.alloc_methodref(pow),
-            [
-                handle_operand(&args[0].node, ctx),
-                conv_f32!(handle_operand(&args[1].node, ctx))
-            ]
-        ),
-        ctx,
-    )
+/// Biggest constant exponent, for which `powi` gets lowered to a chain of multiplications instead of a call to `Pow`.
+const POWI_MUL_CHAIN_LIMIT: i128 = 8;
+/// Multiplies two floats of type `float`. .NET has no `f16` or `f128` multiplication, so those call `mul_f16` / `__multf3`.
+fn float_mul(
+    lhs: CILNode,
+    rhs: CILNode,
+    float: Float,
+    ctx: &mut MethodCompileCtx<'_, '_>,
+) -> CILNode {
+    let name = match float {
+        Float::F32 | Float::F64 => return CILNode::Mul(Box::new(lhs), Box::new(rhs)),
+        Float::F16 => "mul_f16",
+        Float::F128 => "__multf3",
+    };
+    let sig = ctx.sig([Type::Float(float), Type::Float(float)], Type::Float(float));
+    let main_module = *ctx.main_module();
+    let mul = ctx.new_methodref(main_module, name, sig, MethodKind::Static, []);
+    call!(mul, [lhs, rhs])
 }
-pub fn powif64<'tcx>(
+/// Implementation of the powi intrinsics. Small, non-negative constant exponents are lowered to a chain of multiplications,
+/// everything else calls `Math.Pow(x, (double)n)` / `MathF.Pow(x, (float)n)`. `f16` is raised to a power in `f32`,
+/// and `f128` calls `__powitf2` from compiler-builtins.
+pub fn powi<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
     destination: &Place<'tcx>,
-    call_instance: Instance<'tcx>,
+    float: Float,
     ctx: &mut MethodCompileCtx<'tcx, '_>,
 ) -> CILRoot {
     debug_assert_eq!(
         args.len(),
         2,
-        "The intrinsic `powif64` MUST take in exactly 2 arguments!"
+        "The intrinsic `powi` MUST take in exactly 2 arguments!"
     );
-    let pow = MethodRef::new(
-        ClassRef::double(ctx),
-        ctx.alloc_string("Pow"),
-        ctx.sig(
-            [Type::Float(Float::F64), Type::Float(Float::F64)],
-            Type::Float(Float::F64),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-    place_set(
-        destination,
-        call!(
-            ctx.alloc_methodref(pow),
-            [
-                handle_operand(&args[0].node, ctx),
-                conv_f64!(handle_operand(&args[1].node, ctx))
-            ]
-        ),
-        ctx,
-    )
+    let base = handle_operand(&args[0].node, ctx);
+    match (const_int(&args[1].node, ctx), float) {
+        // x^0 is 1, even for NaN.
+        (Some(0), Float::F32) => place_set(destination, CILNode::LdcF32(HashableF32(1.0)), ctx),
+        (Some(0), Float::F64) => place_set(destination, CILNode::LdcF64(HashableF64(1.0)), ctx),
+        (Some(exp @ 1..=POWI_MUL_CHAIN_LIMIT), _) => {
+            let chain = (1..exp).fold(base.clone(), |acc, _| {
+                float_mul(acc, base.clone(), float, ctx)
+            });
+            place_set(destination, chain, ctx)
+        }
+        (_, Float::F32 | Float::F64) => {
+            let exp = handle_operand(&args[1].node, ctx);
+            let exp = match float {
+                Float::F32 => conv_f32!(exp),
+                _ => conv_f64!(exp),
+            };
+            let class = ctx.float_class(float);
+            let pow = ctx.call_math(class, "Pow", [base, exp], Type::Float(float));
+            place_set(destination, pow, ctx)
+        }
+        (_, Float::F16) => {
+            let exp = conv_f32!(handle_operand(&args[1].node, ctx));
+            let half = ClassRef::half(ctx);
+            let to_f32 = ctx.sig([Type::Float(Float::F16)], Type::Float(Float::F32));
+            let to_f32 = ctx.new_methodref(half, "op_Explicit", to_f32, MethodKind::Static, []);
+            let class = ctx.float_class(Float::F32);
+            let pow = ctx.call_math(
+                class,
+                "Pow",
+                [call!(to_f32, [base]), exp],
+                Type::Float(Float::F32),
+            );
+            let to_f16 = ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F16));
+            let to_f16 = ctx.new_methodref(half, "op_Explicit", to_f16, MethodKind::Static, []);
+            place_set(destination, call!(to_f16, [pow]), ctx)
+        }
+        (_, Float::F128) => {
+            let exp = handle_operand(&args[1].node, ctx);
+            let sig = ctx.sig(
+                [Type::Float(Float::F128), Type::Int(Int::I32)],
+                Type::Float(Float::F128),
+            );
+            let main_module = *ctx.main_module();
+            let powi = ctx.new_methodref(main_module, "__powitf2", sig, MethodKind::Static, []);
+            place_set(destination, call!(powi, [base, exp]), ctx)
+        }
+    }
 }
 pub fn powf32<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
@@ -147,23 +226,10 @@ pub fn powf32<'tcx>(
     call_instance: Instance<'tcx>,
     ctx: &mut MethodCompileCtx<'tcx, '_>,
 ) -> CILRoot {
-    let pow = MethodRef::new(
-        ClassRef::single(ctx),
-        ctx.alloc_string("Pow"),
-        ctx.sig(
-            [Type::Float(Float::F32), Type::Float(Float::F32)],
-            Type::Float(Float::F32),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-    let value_calc = call!(
-        ctx.alloc_methodref(pow),
-        [
-            handle_operand(&args[0].node, ctx),
-            handle_operand(&args[1].node, ctx),
-        ]
-    );
+    let class = ctx.float_class(Float::F32);
+    let lhs = handle_operand(&args[0].node, ctx);
+    let rhs = handle_operand(&args[1].node, ctx);
+    let value_calc = ctx.call_math(class, "Pow", [lhs, rhs], Type::Float(Float::F32));
     place_set(destination, value_calc, ctx)
 }
 pub fn powf64<'tcx>(
@@ -172,29 +238,14 @@ pub fn powf64<'tcx>(
     call_instance: Instance<'tcx>,
     ctx: &mut MethodCompileCtx<'tcx, '_>,
 ) -> CILRoot {
-    let pow = MethodRef::new(
-        ClassRef::double(ctx),
-        ctx.alloc_string("Pow"),
-        ctx.sig(
-            [Type::Float(Float::F64), Type::Float(Float::F64)],
-            Type::Float(Float::F64),
-        ),
-        MethodKind::Static,
-        vec![].into(),
-    );
-
-    place_set(
-        destination,
-        call!(
-            ctx.alloc_methodref(pow),
-            [
-                handle_operand(&args[0].node, ctx),
-                handle_operand(&args[1].node, ctx),
-            ]
-        ),
-        ctx,
-    )
+    let class = ctx.float_class(Float::F64);
+    let lhs = handle_operand(&args[0].node, ctx);
+    let rhs = handle_operand(&args[1].node, ctx);
+    let value_calc = ctx.call_math(class, "Pow", [lhs, rhs], Type::Float(Float::F64));
+    place_set(destination, value_calc, ctx)
 }
+/// Implementation of the roundf32 intrinsic. Rounds half-way cases away from zero, using `MidpointRounding.AwayFromZero`.
+/// The sign of the input is preserved when it rounds to zero, so `-0.3` rounds to `-0.0`, like in Rust.
 pub fn roundf32<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
     destination: &Place<'tcx>,
@@ -203,7 +254,7 @@ pub fn roundf32<'tcx>(
 ) -> CILRoot {
     let rounding = ClassRef::midpoint_rounding(ctx);
     let round = MethodRef::new(
-        ClassRef::mathf(ctx),
+        ctx.math_class(Float::F32),
         ctx.alloc_string("Round"),
         ctx.sig(
             [Type::Float(Float::F32), Type::ClassRef(rounding)],
@@ -225,6 +276,7 @@ pub fn roundf32<'tcx>(
     );
     place_set(destination, value_calc, ctx)
 }
+/// Implementation of the roundf64 intrinsic. Like [`roundf32`], preserves the sign of inputs rounding to zero.
 pub fn roundf64<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
     destination: &Place<'tcx>,
@@ -233,7 +285,7 @@ pub fn roundf64<'tcx>(
 ) -> CILRoot {
     let rounding = ClassRef::midpoint_rounding(ctx);
     let round = MethodRef::new(
-        ClassRef::math(ctx),
+        ctx.math_class(Float::F64),
         ctx.alloc_string("Round"),
         ctx.sig(
             [Type::Float(Float::F64), Type::ClassRef(rounding)],
@@ -255,3 +307,92 @@ pub fn roundf64<'tcx>(
     );
     place_set(destination, value_calc, ctx)
 }
+/// Math intrinsics, which get emulated for floats .NET has no math support for: the Rust name, the name of the `System.Single` method, and the name of the libm function.
+const EMULATED_FLOAT_MATH: &[(&str, &str, &str)] = &[
+    ("sqrt", "Sqrt", "sqrt"),
+    ("fma", "FusedMultiplyAdd", "fma"),
+    ("fabs", "Abs", "fabs"),
+    ("floor", "Floor", "floor"),
+    ("ceil", "Ceiling", "ceil"),
+    ("trunc", "Truncate", "trunc"),
+    ("pow", "Pow", "pow"),
+    ("exp", "Exp", "exp"),
+    ("exp2", "Exp2", "exp2"),
+    ("log", "Log", "log"),
+    ("log2", "Log2", "log2"),
+    ("log10", "Log10", "log10"),
+    ("sin", "Sin", "sin"),
+    ("cos", "Cos", "cos"),
+    ("copysign", "CopySign", "copysign"),
+    ("minnum", "MinNumber", "fmin"),
+    ("maxnum", "MaxNumber", "fmax"),
+];
+/// Splits the name of an `f16` or `f128` math intrinsic(eg. `sqrtf16`) into its float type and its entry in [`EMULATED_FLOAT_MATH`].
+fn emulated_float_op(
+    fn_name: &str,
+) -> Option<(Float, &'static (&'static str, &'static str, &'static str))> {
+    let (op, float) = if let Some(op) = fn_name.strip_suffix("f16") {
+        (op, Float::F16)
+    } else {
+        (fn_name.strip_suffix("f128")?, Float::F128)
+    };
+    EMULATED_FLOAT_MATH
+        .iter()
+        .find(|(name, _, _)| *name == op)
+        .map(|entry| (float, entry))
+}
+/// Checks if `fn_name` is a math intrinsic operating on `f16` or `f128`.
+pub fn is_emulated_float_math(fn_name: &str) -> bool {
+    emulated_float_op(fn_name).is_some()
+}
+/// Implements the math intrinsics of `f16` and `f128`, which .NET has no native support for.
+/// `f16` math is computed in `f32`, which is precise enough for the result to be rounded back to `f16`.
+/// `f128` math calls the soft-float implementation from libm(eg. `sqrtf128`).
+pub fn emulated_float_math<'tcx>(
+    fn_name: &str,
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    let (float, (_, dotnet_name, libm_name)) =
+        emulated_float_op(fn_name).unwrap_or_else(|| panic!("{fn_name} is not emulated."));
+    match float {
+        Float::F16 => {
+            let half = ClassRef::half(ctx);
+            let to_f32 = ctx.sig([Type::Float(Float::F16)], Type::Float(Float::F32));
+            let to_f32 = ctx.new_methodref(half, "op_Explicit", to_f32, MethodKind::Static, []);
+            let args: Vec<_> = args
+                .iter()
+                .map(|arg| {
+                    let arg = handle_operand(&arg.node, ctx);
+                    call!(to_f32, [arg])
+                })
+                .collect();
+            let class = ctx.float_class(Float::F32);
+            let res = ctx.call_math(class, dotnet_name, args, Type::Float(Float::F32));
+            let to_f16 = ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F16));
+            let to_f16 = ctx.new_methodref(half, "op_Explicit", to_f16, MethodKind::Static, []);
+            place_set(destination, call!(to_f16, [res]), ctx)
+        }
+        Float::F128 => {
+            let args: Vec<_> = args
+                .iter()
+                .map(|arg| handle_operand(&arg.node, ctx))
+                .collect();
+            let sig = ctx.sig(
+                vec![Type::Float(Float::F128); args.len()],
+                Type::Float(Float::F128),
+            );
+            let main_module = *ctx.main_module();
+            let emulated = ctx.new_methodref(
+                main_module,
+                format!("{libm_name}f128"),
+                sig,
+                MethodKind::Static,
+                [],
+            );
+            place_set(destination, call!(emulated, args), ctx)
+        }
+        _ => unreachable!(),
+    }
+}
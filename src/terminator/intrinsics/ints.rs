@@ -529,6 +529,12 @@ pub fn bitreverse<'tcx>(
             .expect("needs_drop works only on types!"),
     );
     let val_tpe = ctx.type_from_cache(val_tpe);
+    // `bitreverse` never changes the width of its argument.
+    debug_assert_eq!(
+        ctx.type_from_cache(ctx.monomorphize(destination.ty(ctx.body(), ctx.tcx()).ty)),
+        val_tpe,
+        "The result of `bitreverse` must have the same type as its argument!"
+    );
     let val = handle_operand(&args[0].node, ctx);
     place_set(
         destination,
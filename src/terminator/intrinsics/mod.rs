@@ -12,7 +12,7 @@ use cilly::{
     conv_i16, conv_i32, conv_i64, conv_i8, conv_isize, conv_u16, conv_u32, conv_u64, conv_u8,
     conv_usize,
     tpe::int,
-    v2::{ClassRef, Float, Int},
+    v2::{ClassRef, Float, FnSig, Int},
     Const, FieldDesc, IntoAsmIndex, MethodRef, Type,
 };
 use ints::{ctlz, rotate_left, rotate_right};
@@ -22,6 +22,10 @@ use rustc_middle::{
 };
 use rustc_span::source_map::Spanned;
 use saturating::{saturating_add, saturating_sub};
+use simd::{
+    simd_binop, simd_cast_ptr, simd_eq, simd_gather_scatter, simd_reduce, simd_reduce_mask,
+    simd_select, simd_unop,
+};
 use type_info::{is_val_statically_known, size_of_val};
 use utilis::{
     atomic_add, atomic_and, atomic_max, atomic_min, atomic_nand, atomic_or, atomic_xor,
@@ -32,9 +36,10 @@ mod floats;
 mod interop;
 mod ints;
 mod saturating;
+mod simd;
 mod type_info;
 mod utilis;
-use floats::{fmaf32, fmaf64, powf32, powf64, powif32, powif64, roundf32, roundf64};
+use floats::{copysign, fabs, fmaf32, fmaf64, powf32, powf64, powi, roundf32, roundf64};
 mod ptr;
 use ptr::arith_offset;
 mod mem;
@@ -523,6 +528,25 @@ pub fn handle_intrinsic<'tcx>(
                 ctx,
             )]
         }
+        // Usually lowered to `NullOp::AlignOf` by rustc. Used by `ptr::dangling` and `NonNull::dangling`, whose address must be equal to the aligement.
+        "min_align_of" | "align_of" => {
+            debug_assert_eq!(
+                args.len(),
+                0,
+                "The intrinsic `{fn_name}` MUST take in no arguments!"
+            );
+            let tpe = ctx.monomorphize(
+                call_instance.args[0]
+                    .as_type()
+                    .expect("align_of works only on types!"),
+            );
+            let align = crate::utilis::align_of(tpe, ctx.tcx());
+            vec![place_set(
+                destination,
+                CILNode::V2(ctx.alloc_node(Const::USize(align))),
+                ctx,
+            )]
+        }
         // .NET guarantess all loads are tear-free
         "atomic_load_relaxed" => {
             //I am not sure this is implemented propely
@@ -544,21 +568,10 @@ pub fn handle_intrinsic<'tcx>(
                 1,
                 "The intrinsic `sqrtf32` MUST take in exactly 1 argument!"
             );
-            let sqrt = MethodRef::new(
-                ClassRef::mathf(ctx),
-                ctx.alloc_string("Sqrt"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            vec![place_set(
-                destination,
-                call!(
-                    ctx.alloc_methodref(sqrt),
-                    [handle_operand(&args[0].node, ctx)]
-                ),
-                ctx,
-            )]
+            let class = ctx.math_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let sqrt = ctx.call_math(class, "Sqrt", [val], Type::Float(Float::F32));
+            vec![place_set(destination, sqrt, ctx)]
         }
         "carrying_mul_add" => {
             let wrapping = ctx.type_from_cache(
@@ -701,9 +714,28 @@ pub fn handle_intrinsic<'tcx>(
                 },
             ]
         }
-        "powif32" => vec![powif32(args, destination, call_instance, ctx)],
-        "powif64" => vec![powif64(args, destination, call_instance, ctx)],
+        "powif16" => vec![powi(args, destination, Float::F16, ctx)],
+        "powif32" => vec![powi(args, destination, Float::F32, ctx)],
+        "powif64" => vec![powi(args, destination, Float::F64, ctx)],
+        "powif128" => vec![powi(args, destination, Float::F128, ctx)],
         "size_of_val" => vec![size_of_val(args, destination, ctx, call_instance)],
+        // Usually lowered to `CastKind::Transmute` by rustc, but may still end up as calls.
+        "transmute" | "transmute_unchecked" => {
+            let [src, dst] = [0, 1].map(|idx| {
+                let tpe = ctx.monomorphize(
+                    call_instance.args[idx]
+                        .as_type()
+                        .expect("transmute works only on types!"),
+                );
+                ctx.type_from_cache(tpe)
+            });
+            let val = handle_operand(&args[0].node, ctx);
+            vec![place_set(
+                destination,
+                crate::transmute::transmute(src, dst, val, ctx),
+                ctx,
+            )]
+        }
         "typed_swap_nonoverlapping" => {
             let pointed_ty = ctx.monomorphize(
                 call_instance.args[0]
@@ -772,474 +804,208 @@ pub fn handle_intrinsic<'tcx>(
                 ctx,
             )]
         }
-        "fabsf32" => {
-            let abs = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Abs"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(abs),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
-        }
-        "fabsf64" => {
-            let abs = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Abs"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-
-            vec![place_set(
-                destination,
-                call!(
-                    ctx.alloc_methodref(abs),
-                    [handle_operand(&args[0].node, ctx),]
-                ),
-                ctx,
-            )]
-        }
+        "fabsf32" => vec![fabs(args, destination, Float::F32, ctx)],
+        "fabsf64" => vec![fabs(args, destination, Float::F64, ctx)],
         "expf32" => {
-            let exp = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Exp"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(exp),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let exp = ctx.call_math(class, "Exp", [val], Type::Float(Float::F32));
+            vec![place_set(destination, exp, ctx)]
         }
         "expf64" => {
-            let exp = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Exp"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(exp),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let exp = ctx.call_math(class, "Exp", [val], Type::Float(Float::F64));
+            vec![place_set(destination, exp, ctx)]
         }
         "logf32" => {
-            let log = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Log"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(log),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log", [val], Type::Float(Float::F32));
+            vec![place_set(destination, log, ctx)]
         }
         "logf64" => {
-            let log = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Log"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            vec![place_set(
-                destination,
-                call!(
-                    ctx.alloc_methodref(log),
-                    [handle_operand(&args[0].node, ctx),]
-                ),
-                ctx,
-            )]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log", [val], Type::Float(Float::F64));
+            vec![place_set(destination, log, ctx)]
         }
         "log2f32" => {
-            let log = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Log2"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(log),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log2", [val], Type::Float(Float::F32));
+            vec![place_set(destination, log, ctx)]
         }
         "log2f64" => {
-            let log = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Log2"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(log),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log2", [val], Type::Float(Float::F64));
+            vec![place_set(destination, log, ctx)]
         }
         "log10f32" => {
-            let log = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Log10"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(log),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log10", [val], Type::Float(Float::F32));
+            vec![place_set(destination, log, ctx)]
         }
         "log10f64" => {
-            let log = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Log10"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(log),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let log = ctx.call_math(class, "Log10", [val], Type::Float(Float::F64));
+            vec![place_set(destination, log, ctx)]
         }
         "powf32" => vec![powf32(args, destination, call_instance, ctx)],
         "powf64" => vec![powf64(args, destination, call_instance, ctx)],
-        "copysignf32" => {
-            let copy_sign = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("CopySign"),
-                ctx.sig(
-                    [Type::Float(Float::F32), Type::Float(Float::F32)],
-                    Type::Float(Float::F32),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(copy_sign),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
-        }
-        "copysignf64" => {
-            let copy_sign = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("CopySign"),
-                ctx.sig(
-                    [Type::Float(Float::F64), Type::Float(Float::F64)],
-                    Type::Float(Float::F64),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(copy_sign),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
-        }
+        "copysignf32" => vec![copysign(args, destination, Float::F32, ctx)],
+        "copysignf64" => vec![copysign(args, destination, Float::F64, ctx)],
         "sinf32" => {
-            let sin = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Sin"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(sin),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let sin = ctx.call_math(class, "Sin", [val], Type::Float(Float::F32));
+            vec![place_set(destination, sin, ctx)]
         }
         "sinf64" => {
-            let sin = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Sin"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(sin),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let sin = ctx.call_math(class, "Sin", [val], Type::Float(Float::F64));
+            vec![place_set(destination, sin, ctx)]
         }
         "cosf32" => {
-            let cos = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Cos"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(cos),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let cos = ctx.call_math(class, "Cos", [val], Type::Float(Float::F32));
+            vec![place_set(destination, cos, ctx)]
         }
         "cosf64" => {
-            let cos = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Cos"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(cos),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let cos = ctx.call_math(class, "Cos", [val], Type::Float(Float::F64));
+            vec![place_set(destination, cos, ctx)]
         }
+        // `Exp2` is exact for integer exponents, unlike `Pow(2, x)`. It is provided by `Single` and `Double` since .NET 7,
+        // so we don't need a `Pow` fallback for the `net8.0` target.
         "exp2f32" => {
-            let exp = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("Exp2"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(exp),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let exp = ctx.call_math(class, "Exp2", [val], Type::Float(Float::F32));
+            vec![place_set(destination, exp, ctx)]
         }
         "exp2f64" => {
-            let exp = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("Exp2"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(exp),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let exp = ctx.call_math(class, "Exp2", [val], Type::Float(Float::F64));
+            vec![place_set(destination, exp, ctx)]
         }
         "truncf32" => {
-            let trunc = MethodRef::new(
-                ClassRef::mathf(ctx),
-                ctx.alloc_string("Truncate"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(trunc),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let trunc = ctx.call_math(class, "Truncate", [val], Type::Float(Float::F32));
+            vec![place_set(destination, trunc, ctx)]
         }
         "truncf64" => {
-            let trunc = MethodRef::new(
-                ClassRef::math(ctx),
-                ctx.alloc_string("Truncate"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(trunc),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let trunc = ctx.call_math(class, "Truncate", [val], Type::Float(Float::F64));
+            vec![place_set(destination, trunc, ctx)]
         }
         // `roundf32` should be a differnt intrinsics, but it requires some .NET fuckery to implement(.NET enums are **wierd**)
         "nearbyintf32" | "rintf32" | "roundevenf32" => {
-            let round = MethodRef::new(
-                ClassRef::mathf(ctx),
-                ctx.alloc_string("Round"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(round),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let round = ctx.call_math(class, "Round", [val], Type::Float(Float::F32));
+            vec![place_set(destination, round, ctx)]
         }
         "roundf32" => vec![roundf32(args, destination, ctx)],
         "roundf64" => vec![roundf64(args, destination, ctx)],
         "nearbyintf64" | "rintf64" | "roundevenf64" => {
-            let round = MethodRef::new(
-                ClassRef::math(ctx),
-                ctx.alloc_string("Round"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(round),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let round = ctx.call_math(class, "Round", [val], Type::Float(Float::F64));
+            vec![place_set(destination, round, ctx)]
         }
 
         "floorf32" => {
-            let floor = MethodRef::new(
-                ClassRef::mathf(ctx),
-                ctx.alloc_string("Floor"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(floor),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let floor = ctx.call_math(class, "Floor", [val], Type::Float(Float::F32));
+            vec![place_set(destination, floor, ctx)]
         }
         "floorf64" => {
-            let floor = MethodRef::new(
-                ClassRef::math(ctx),
-                ctx.alloc_string("Floor"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(floor),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let floor = ctx.call_math(class, "Floor", [val], Type::Float(Float::F64));
+            vec![place_set(destination, floor, ctx)]
         }
         "ceilf32" => {
-            let ceil = MethodRef::new(
-                ClassRef::mathf(ctx),
-                ctx.alloc_string("Ceiling"),
-                ctx.sig([Type::Float(Float::F32)], Type::Float(Float::F32)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(ceil),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F32);
+            let val = handle_operand(&args[0].node, ctx);
+            let ceil = ctx.call_math(class, "Ceiling", [val], Type::Float(Float::F32));
+            vec![place_set(destination, ceil, ctx)]
         }
         "ceilf64" => {
-            let ceil = MethodRef::new(
-                ClassRef::math(ctx),
-                ctx.alloc_string("Ceiling"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(ceil),
-                [handle_operand(&args[0].node, ctx),]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.math_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let ceil = ctx.call_math(class, "Ceiling", [val], Type::Float(Float::F64));
+            vec![place_set(destination, ceil, ctx)]
         }
+        // `MaxNumber` and `MinNumber` implement IEEE 754-2019 `maximumNumber`/`minimumNumber`: if one of the operands is NaN, the other one is returned,
+        // and -0.0 is treated as less than +0.0. This matches the semantics of Rust's `max` and `min`.
         "maxnumf64" => {
-            let max = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("MaxNumber"),
-                ctx.sig(
-                    [Type::Float(Float::F64), Type::Float(Float::F64)],
-                    Type::Float(Float::F64),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(max),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let max = ctx.call_math(class, "MaxNumber", [lhs, rhs], Type::Float(Float::F64));
+            vec![place_set(destination, max, ctx)]
         }
         "maxnumf32" => {
-            let max = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("MaxNumber"),
-                ctx.sig(
-                    [Type::Float(Float::F32), Type::Float(Float::F32)],
-                    Type::Float(Float::F32),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(max),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let max = ctx.call_math(class, "MaxNumber", [lhs, rhs], Type::Float(Float::F32));
+            vec![place_set(destination, max, ctx)]
         }
         "minnumf64" => {
-            let min = MethodRef::new(
-                ClassRef::double(ctx),
-                ctx.alloc_string("MinNumber"),
-                ctx.sig(
-                    [Type::Float(Float::F64), Type::Float(Float::F64)],
-                    Type::Float(Float::F64),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(min),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F64);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let min = ctx.call_math(class, "MinNumber", [lhs, rhs], Type::Float(Float::F64));
+            vec![place_set(destination, min, ctx)]
         }
         "minnumf32" => {
-            let min = MethodRef::new(
-                ClassRef::single(ctx),
-                ctx.alloc_string("MinNumber"),
-                ctx.sig(
-                    [Type::Float(Float::F32), Type::Float(Float::F32)],
-                    Type::Float(Float::F32),
-                ),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let value_calc = call!(
-                ctx.alloc_methodref(min),
-                [
-                    handle_operand(&args[0].node, ctx),
-                    handle_operand(&args[1].node, ctx),
-                ]
-            );
-            vec![place_set(destination, value_calc, ctx)]
+            let class = ctx.float_class(Float::F32);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let min = ctx.call_math(class, "MinNumber", [lhs, rhs], Type::Float(Float::F32));
+            vec![place_set(destination, min, ctx)]
+        }
+        // `Min` and `Max` implement IEEE 754-2019 `minimum`/`maximum`: NaNs are propagated, and -0.0 is treated as less than +0.0.
+        "minimumf32" => {
+            let class = ctx.math_class(Float::F32);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let min = ctx.call_math(class, "Min", [lhs, rhs], Type::Float(Float::F32));
+            vec![place_set(destination, min, ctx)]
+        }
+        "minimumf64" => {
+            let class = ctx.math_class(Float::F64);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let min = ctx.call_math(class, "Min", [lhs, rhs], Type::Float(Float::F64));
+            vec![place_set(destination, min, ctx)]
+        }
+        "maximumf32" => {
+            let class = ctx.math_class(Float::F32);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let max = ctx.call_math(class, "Max", [lhs, rhs], Type::Float(Float::F32));
+            vec![place_set(destination, max, ctx)]
+        }
+        "maximumf64" => {
+            let class = ctx.math_class(Float::F64);
+            let lhs = handle_operand(&args[0].node, ctx);
+            let rhs = handle_operand(&args[1].node, ctx);
+            let max = ctx.call_math(class, "Max", [lhs, rhs], Type::Float(Float::F64));
+            vec![place_set(destination, max, ctx)]
         }
         "variant_count" => {
             let const_val = ctx
@@ -1266,18 +1032,10 @@ pub fn handle_intrinsic<'tcx>(
                 1,
                 "The intrinsic `sqrtf64` MUST take in exactly 1 argument!"
             );
-            let sqrt = MethodRef::new(
-                ClassRef::math(ctx),
-                ctx.alloc_string("Sqrt"),
-                ctx.sig([Type::Float(Float::F64)], Type::Float(Float::F64)),
-                MethodKind::Static,
-                vec![].into(),
-            );
-            let ops = call!(
-                ctx.alloc_methodref(sqrt),
-                [handle_operand(&args[0].node, ctx)]
-            );
-            vec![place_set(destination, ops, ctx)]
+            let class = ctx.math_class(Float::F64);
+            let val = handle_operand(&args[0].node, ctx);
+            let sqrt = ctx.call_math(class, "Sqrt", [val], Type::Float(Float::F64));
+            vec![place_set(destination, sqrt, ctx)]
         }
         "rotate_right" => vec![rotate_right(args, destination, ctx, call_instance)],
         "catch_unwind" => {
@@ -1290,6 +1048,17 @@ pub fn handle_intrinsic<'tcx>(
             let data_ptr = handle_operand(&args[1].node, ctx);
             let catch_fn = handle_operand(&args[2].node, ctx);
             let uint8_ptr = ctx.nptr(Type::Int(Int::U8));
+            // Under `panic=abort`, panics can't be caught, so no handler is needed: the try function gets called directly.
+            if ctx.tcx().sess.panic_strategy() == rustc_target::spec::PanicStrategy::Abort {
+                return vec![
+                    CILRoot::CallI {
+                        sig: Box::new(FnSig::new(Box::new([uint8_ptr]), Type::Void)),
+                        fn_ptr: Box::new(try_fn),
+                        args: [data_ptr].into(),
+                    },
+                    place_set(destination, CILNode::V2(ctx.alloc_node(0_i32)), ctx),
+                ];
+            }
             let try_ptr = ctx.sig([uint8_ptr], Type::Void);
             let catch_ptr = ctx.sig([uint8_ptr, uint8_ptr], Type::Void);
             let catch_unwind = MethodRef::new(
@@ -1319,114 +1088,51 @@ pub fn handle_intrinsic<'tcx>(
         )],
         "vtable_size" => vec![vtable::vtable_size(args, destination, ctx)],
         "vtable_align" => vec![vtable::vtable_align(args, destination, ctx)],
-        "simd_eq" => {
-            let comparands = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let result = ctx.type_from_cache(
-                call_instance.args[1]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_eq");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[comparands, comparands], result, name, ctx);
-            vec![place_set(destination, call!(eq, [lhs, rhs]), ctx)]
-        }
-        "simd_or" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_or works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_or");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec, vec], vec, name, ctx);
-            vec![place_set(destination, call!(eq, [lhs, rhs]), ctx)]
-        }
-        "simd_add" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_add works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_add");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec, vec], vec, name, ctx);
-            vec![place_set(destination, call!(eq, [lhs, rhs]), ctx)]
-        }
-        "simd_and" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_and works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_and");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec, vec], vec, name, ctx);
-            vec![place_set(destination, call!(eq, [lhs, rhs]), ctx)]
-        }
-        "simd_sub" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_sub works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_sub");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let sub = main_module.static_mref(&[vec, vec], vec, name, ctx);
-            vec![place_set(destination, call!(sub, [lhs, rhs]), ctx)]
-        }
-        "simd_mul" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_mul works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let name = ctx.alloc_string("simd_mul");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let mul = main_module.static_mref(&[vec, vec], vec, name, ctx);
-            vec![place_set(destination, call!(mul, [lhs, rhs]), ctx)]
-        }
-        "simd_fabs" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_fabs works only on types!"),
-            );
-
-            let lhs = handle_operand(&args[0].node, ctx);
-            let name = ctx.alloc_string("simd_abs");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let abs = main_module.static_mref(&[vec], vec, name, ctx);
-            vec![place_set(destination, call!(abs, [lhs,]), ctx)]
-        }
+        "simd_eq" => simd_eq(args, destination, call_instance, false, ctx),
+        "simd_or" => vec![simd_binop(args, destination, call_instance, "simd_or", ctx)],
+        "simd_add" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_add",
+            ctx,
+        )],
+        "simd_and" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_and",
+            ctx,
+        )],
+        "simd_sub" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_sub",
+            ctx,
+        )],
+        "simd_mul" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_mul",
+            ctx,
+        )],
+        "simd_div" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_div",
+            ctx,
+        )],
+        "simd_xor" => vec![simd_binop(
+            args,
+            destination,
+            call_instance,
+            "simd_xor",
+            ctx,
+        )],
+        "simd_fabs" => vec![simd_unop(args, destination, call_instance, "simd_abs", ctx)],
         "simd_bitmask" => {
             let vec: Type = ctx.type_from_cache(
                 call_instance.args[0]
@@ -1452,18 +1158,64 @@ pub fn handle_intrinsic<'tcx>(
                 ctx,
             )]
         }
-        "simd_neg" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_neg works only on types!"),
-            );
-            let val = handle_operand(&args[0].node, ctx);
-            let name = ctx.alloc_string("simd_neg");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec], vec, name, ctx);
-            vec![place_set(destination, call!(eq, [val]), ctx)]
+        "simd_neg" => vec![simd_unop(args, destination, call_instance, "simd_neg", ctx)],
+        "simd_fsqrt" => vec![simd_unop(
+            args,
+            destination,
+            call_instance,
+            "simd_sqrt",
+            ctx,
+        )],
+        "simd_ceil" => vec![simd_unop(
+            args,
+            destination,
+            call_instance,
+            "simd_ceil",
+            ctx,
+        )],
+        "simd_floor" => vec![simd_unop(
+            args,
+            destination,
+            call_instance,
+            "simd_floor",
+            ctx,
+        )],
+        "simd_select" => vec![simd_select(args, destination, call_instance, ctx)],
+        "simd_cast_ptr" => vec![simd_cast_ptr(args, destination, call_instance, ctx)],
+        "simd_gather" | "simd_scatter" => vec![simd_gather_scatter(
+            args,
+            destination,
+            call_instance,
+            fn_name,
+            ctx,
+        )],
+        "simd_reduce_add_ordered" | "simd_reduce_mul_ordered" => {
+            let helper_name = fn_name.trim_end_matches("_ordered");
+            vec![simd_reduce(
+                args,
+                destination,
+                call_instance,
+                helper_name,
+                true,
+                ctx,
+            )]
+        }
+        "simd_reduce_add_unordered"
+        | "simd_reduce_mul_unordered"
+        | "simd_reduce_and"
+        | "simd_reduce_or"
+        | "simd_reduce_xor"
+        | "simd_reduce_min"
+        | "simd_reduce_max" => {
+            let helper_name = fn_name.trim_end_matches("_unordered");
+            vec![simd_reduce(
+                args,
+                destination,
+                call_instance,
+                helper_name,
+                false,
+                ctx,
+            )]
         }
         "simd_shuffle" => {
             let t_type = ctx.type_from_cache(
@@ -1481,6 +1233,35 @@ pub fn handle_intrinsic<'tcx>(
                     .as_type()
                     .expect("simd_eq works only on types!"),
             );
+            if cfg!(debug_assertions) {
+                if let Err(err) =
+                    cilly::v2::builtins::simd::validate_shuffle(t_type, u_type, v_type)
+                {
+                    panic!("Invalid simd_shuffle in {call_instance:?}: {err}");
+                }
+            }
+            // The indices are almost always constants, so they can be checked and optimized at compile time.
+            let const_idxs: Option<Vec<u64>> = crate::operand::const_bytes(&args[2].node, ctx)
+                .zip(u_type.as_simdvector())
+                .map(|(bytes, idx_vec)| {
+                    let lane_size = bytes.len() / usize::from(idx_vec.count());
+                    bytes
+                        .chunks(lane_size)
+                        .map(|lane| {
+                            let mut idx = [0; 8];
+                            idx[..lane.len()].copy_from_slice(lane);
+                            u64::from_le_bytes(idx)
+                        })
+                        .collect()
+                });
+            if cfg!(debug_assertions) {
+                if let Some(idxs) = &const_idxs {
+                    if let Err(err) = cilly::v2::builtins::simd::validate_shuffle_idxs(t_type, idxs)
+                    {
+                        panic!("Invalid simd_shuffle in {call_instance:?}: {err}");
+                    }
+                }
+            }
             let x = handle_operand(&args[0].node, ctx);
             let y = handle_operand(&args[1].node, ctx);
             // When the two vectors provided to simd shuffles are always the same, and have a length of 1(are scalar), the shuffle is equivalent to creating a vector [scalar,scalar].
@@ -1497,51 +1278,30 @@ pub fn handle_intrinsic<'tcx>(
                 return vec![place_set(destination, call!(shuffle, [x]), ctx)];
             }
             let idx = handle_operand(&args[2].node, ctx);
+            // Shuffles of a single hardware vector can use `VectorN.Shuffle`.
+            if const_idxs.is_some_and(|idxs| {
+                cilly::v2::builtins::simd::supports_dotnet_shuffle(t_type, v_type, &idxs)
+            }) {
+                let name = ctx.alloc_string("simd_shuffle_dotnet");
+                let main_module = ctx.main_module();
+                let main_module = ctx[*main_module].clone();
+                let shuffle = main_module.static_mref(&[t_type, u_type], v_type, name, ctx);
+                return vec![place_set(destination, call!(shuffle, [x, idx]), ctx)];
+            }
             let name = ctx.alloc_string("simd_shuffle");
             let main_module = ctx.main_module();
             let main_module = ctx[*main_module].clone();
             let shuffle = main_module.static_mref(&[t_type, t_type, u_type], v_type, name, ctx);
             vec![place_set(destination, call!(shuffle, [x, y, idx]), ctx)]
         }
-        "simd_ne" => {
-            let comparands = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let result = ctx.type_from_cache(
-                call_instance.args[1]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let lhs = handle_operand(&args[0].node, ctx);
-            let rhs = handle_operand(&args[1].node, ctx);
-            let eq = ctx.alloc_string("simd_eq");
-            let ones_compliment = ctx.alloc_string("simd_ones_compliment");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[comparands, comparands], result, eq, ctx);
-            let eq = call!(eq, [lhs, rhs]);
-            let ones_compliment = main_module.static_mref(&[result], result, ones_compliment, ctx);
-            let ne = call!(ones_compliment, [eq]);
-            vec![place_set(destination, ne, ctx)]
-        }
-        "simd_reduce_any" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let x = handle_operand(&args[0].node, ctx);
-            let simd_eq = ctx.alloc_string("simd_eq_any");
-            let allset = ctx.alloc_string("simd_allset");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec, vec], Type::Bool, simd_eq, ctx);
-            let allset = main_module.static_mref(&[], vec, allset, ctx);
-            let allset = call!(allset, []);
-            vec![place_set(destination, call!(eq, [x, allset]), ctx)]
-        }
+        "simd_ne" => simd_eq(args, destination, call_instance, true, ctx),
+        "simd_reduce_any" | "simd_reduce_all" => vec![simd_reduce_mask(
+            args,
+            destination,
+            call_instance,
+            fn_name,
+            ctx,
+        )],
         "select_unpredictable" => {
             let tpe = ctx.type_from_cache(
                 call_instance.args[0]
@@ -1555,21 +1315,8 @@ pub fn handle_intrinsic<'tcx>(
             let select = CILNode::select(tpe, true_val, false_val, cond, ctx);
             vec![place_set(destination, select, ctx)]
         }
-        "simd_reduce_all" => {
-            let vec = ctx.type_from_cache(
-                call_instance.args[0]
-                    .as_type()
-                    .expect("simd_eq works only on types!"),
-            );
-            let x = handle_operand(&args[0].node, ctx);
-            let simd_eq = ctx.alloc_string("simd_eq_all");
-            let allset = ctx.alloc_string("simd_allset");
-            let main_module = ctx.main_module();
-            let main_module = ctx[*main_module].clone();
-            let eq = main_module.static_mref(&[vec, vec], Type::Bool, simd_eq, ctx);
-            let allset = main_module.static_mref(&[], vec, allset, ctx);
-            let allset = call!(allset, []);
-            vec![place_set(destination, call!(eq, [x, allset]), ctx)]
+        _ if floats::is_emulated_float_math(fn_name) => {
+            vec![floats::emulated_float_math(fn_name, args, destination, ctx)]
         }
         _ => intrinsic_slow(fn_name, args, destination, ctx, call_instance, span),
     }
@@ -1,6 +1,6 @@
 use crate::{assembly::MethodCompileCtx, operand::handle_operand, place::place_set};
 use cilly::{
-    cil_node::CILNode, cil_root::CILRoot, conv_isize, conv_usize, Int, IntoAsmIndex, Type,
+    cil_node::CILNode, cil_root::CILRoot, conv_isize, conv_usize, Const, Int, IntoAsmIndex, Type,
 };
 use rustc_middle::{
     mir::{Operand, Place},
@@ -19,7 +19,7 @@ pub fn arith_offset<'tcx>(
             .expect("arith_offset works only on types!"),
     );
     let tpe = ctx.type_from_cache(tpe);
-
+    // `arith_offset` wraps on overflow. The plain CIL `mul` and `add` wrap too, so large offsets or elements need no special handling.
     place_set(
         destination,
         handle_operand(&args[0].node, ctx)
@@ -79,11 +79,16 @@ pub fn ptr_offset_from<'tcx>(
             .as_type()
             .expect("needs_drop works only on types!"),
     );
-    // This is UB, so we can do whatever.
+    // The distance between ZSTs can't be measured in elements, so we say that all ZSTs are at the same offset. This way, `ptr_offset_from(p, p)` is 0 even for ZSTs.
     if ctx.layout_of(ty).is_zst() {
-        return CILRoot::throw(&format!("ptr_offset_from called with zst type:{ty}"), ctx);
+        return place_set(
+            destination,
+            CILNode::V2(ctx.alloc_node(Const::ISize(0))),
+            ctx,
+        );
     }
     let tpe = ctx.type_from_cache(ty);
+    // Identical pointers have a difference of 0 bytes, which is always 0 elements after the division.
 
     place_set(
         destination,
@@ -0,0 +1,415 @@
+use crate::{
+    assembly::MethodCompileCtx,
+    operand::{handle_operand, operand_address},
+    place::{deref_op, place_adress, place_set, PlaceTy},
+};
+use cilly::{call, cil_node::CILNode, cil_root::CILRoot, Const, Int, Type};
+use rustc_middle::{
+    mir::{Operand, Place},
+    ty::Instance,
+};
+use rustc_span::source_map::Spanned;
+/// Implements an element-wise binary SIMD intrinsic, by calling the builtin `helper_name(vec, vec) -> vec`.
+pub fn simd_binop<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    helper_name: &str,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        2,
+        "The intrinsic `{helper_name}` MUST take in exactly 2 arguments!"
+    );
+    let vec_ty = call_instance.args[0]
+        .as_type()
+        .unwrap_or_else(|| panic!("{helper_name} works only on types!"));
+    let vec = ctx.type_from_cache(vec_ty);
+    if vec.as_simdvector().is_none() {
+        let (count, elem_ty) = vec_ty.simd_size_and_type(ctx.tcx());
+        let elem_ptr = ctx.type_from_cache(elem_ty);
+        let elem_ptr = ctx.nptr(elem_ptr);
+        let dst = place_adress(destination, ctx);
+        let lhs = operand_address(&args[0].node, ctx);
+        let rhs = operand_address(&args[1].node, ctx);
+        return call_lanes_helper(
+            helper_name,
+            [(dst, elem_ptr), (lhs, elem_ptr), (rhs, elem_ptr)],
+            count,
+            ctx,
+        );
+    }
+    let lhs = handle_operand(&args[0].node, ctx);
+    let rhs = handle_operand(&args[1].node, ctx);
+    // Vectors wider than what the target features guarantee hardware support for check for it at runtime.
+    let name = match vec.as_simdvector() {
+        Some(simd) if simd.bits() > hardware_vector_bits(ctx) => {
+            ctx.alloc_string(format!("{helper_name}_guarded"))
+        }
+        _ => ctx.alloc_string(helper_name),
+    };
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let op = main_module.static_mref(&[vec, vec], vec, name, ctx);
+    place_set(destination, call!(op, [lhs, rhs]), ctx)
+}
+/// Calls `{helper_name}_lanes(ptrs.., count: usize)`, the variant of a SIMD helper used for vectors with no .NET vector type(eg. with 3 lanes).
+/// Such vectors are operated on lane by lane, trough pointers: each of `ptrs` is the address of a vector, cast to the pointer type next to it.
+fn call_lanes_helper<const N: usize>(
+    helper_name: &str,
+    ptrs: [(CILNode, Type); N],
+    count: u64,
+    ctx: &mut MethodCompileCtx<'_, '_>,
+) -> CILRoot {
+    let name = ctx.alloc_string(format!("{helper_name}_lanes"));
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let inputs: Vec<_> = ptrs
+        .iter()
+        .map(|(_, ptr)| *ptr)
+        .chain([Type::Int(Int::USize)])
+        .collect();
+    let op = main_module.static_mref(&inputs, Type::Void, name, ctx);
+    let count = CILNode::V2(ctx.alloc_node(Const::USize(count)));
+    CILRoot::Call {
+        site: op,
+        args: ptrs
+            .into_iter()
+            .map(|(addr, ptr)| addr.cast_ptr(ptr))
+            .chain([count])
+            .collect(),
+    }
+}
+/// The width, in bits, of the widest vectors the function being compiled can assume hardware support for. This depends on the target features enabled
+/// for the whole crate(`-C target-feature`) and on the `#[target_feature]`s of the function itself. 128 bit vectors are supported by all targets .NET runs on.
+fn hardware_vector_bits(ctx: &MethodCompileCtx<'_, '_>) -> u16 {
+    let attrs = ctx.tcx().codegen_fn_attrs(ctx.instance().def_id());
+    attrs
+        .target_features
+        .iter()
+        .map(|feature| feature.name)
+        .chain(ctx.tcx().sess.target_features.iter().copied())
+        .map(|feature| match feature.as_str() {
+            "avx512f" => 512,
+            "avx" | "avx2" => 256,
+            _ => 128,
+        })
+        .max()
+        .unwrap_or(128)
+}
+/// Implements an element-wise unary SIMD intrinsic, by calling the builtin `helper_name(vec) -> vec`.
+pub fn simd_unop<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    helper_name: &str,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        1,
+        "The intrinsic `{helper_name}` MUST take in exactly 1 argument!"
+    );
+    let vec_ty = call_instance.args[0]
+        .as_type()
+        .unwrap_or_else(|| panic!("{helper_name} works only on types!"));
+    let vec = ctx.type_from_cache(vec_ty);
+    if vec.as_simdvector().is_none() {
+        let (count, elem_ty) = vec_ty.simd_size_and_type(ctx.tcx());
+        let elem_ptr = ctx.type_from_cache(elem_ty);
+        let elem_ptr = ctx.nptr(elem_ptr);
+        let dst = place_adress(destination, ctx);
+        let src = operand_address(&args[0].node, ctx);
+        return call_lanes_helper(helper_name, [(dst, elem_ptr), (src, elem_ptr)], count, ctx);
+    }
+    let val = handle_operand(&args[0].node, ctx);
+    let name = ctx.alloc_string(helper_name);
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let op = main_module.static_mref(&[vec], vec, name, ctx);
+    place_set(destination, call!(op, [val]), ctx)
+}
+/// Implements `simd_eq`, and `simd_ne` when `negate` is set, by calling the builtin `simd_eq(lhs, rhs) -> mask`. `simd_ne` flips all the bits of the mask,
+/// using `simd_ones_compliment`.
+pub fn simd_eq<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    negate: bool,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> Vec<CILRoot> {
+    debug_assert_eq!(
+        args.len(),
+        2,
+        "The intrinsic `simd_eq` MUST take in exactly 2 arguments!"
+    );
+    let [comparands_ty, result_ty] = [0, 1].map(|idx| {
+        call_instance.args[idx]
+            .as_type()
+            .expect("simd_eq works only on types!")
+    });
+    let [comparands, result] = [comparands_ty, result_ty].map(|tpe| ctx.type_from_cache(tpe));
+    if comparands.as_simdvector().is_none() || result.as_simdvector().is_none() {
+        let (count, elem) = comparands_ty.simd_size_and_type(ctx.tcx());
+        let (_, mask_elem) = result_ty.simd_size_and_type(ctx.tcx());
+        let [elem_ptr, mask_ptr] = [elem, mask_elem].map(|elem| {
+            let elem = ctx.type_from_cache(elem);
+            ctx.nptr(elem)
+        });
+        let dst = place_adress(destination, ctx);
+        let lhs = operand_address(&args[0].node, ctx);
+        let rhs = operand_address(&args[1].node, ctx);
+        let mut roots = vec![call_lanes_helper(
+            "simd_eq",
+            [(dst.clone(), mask_ptr), (lhs, elem_ptr), (rhs, elem_ptr)],
+            count,
+            ctx,
+        )];
+        if negate {
+            roots.push(call_lanes_helper(
+                "simd_ones_compliment",
+                [(dst.clone(), mask_ptr), (dst, mask_ptr)],
+                count,
+                ctx,
+            ));
+        }
+        return roots;
+    }
+    let lhs = handle_operand(&args[0].node, ctx);
+    let rhs = handle_operand(&args[1].node, ctx);
+    let name = ctx.alloc_string("simd_eq");
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let eq = main_module.static_mref(&[comparands, comparands], result, name, ctx);
+    let mut res = call!(eq, [lhs, rhs]);
+    if negate {
+        let ones_compliment = ctx.alloc_string("simd_ones_compliment");
+        let ones_compliment = main_module.static_mref(&[result], result, ones_compliment, ctx);
+        res = call!(ones_compliment, [res]);
+    }
+    vec![place_set(destination, res, ctx)]
+}
+/// Implements `simd_gather` and `simd_scatter`, by calling the builtins with the same name. Both take in the values, the pointers, and the mask.
+pub fn simd_gather_scatter<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    helper_name: &str,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        3,
+        "The intrinsic `{helper_name}` MUST take in exactly 3 arguments!"
+    );
+    let [t_type, u_type, v_type] = [0, 1, 2].map(|idx| {
+        ctx.type_from_cache(
+            call_instance.args[idx]
+                .as_type()
+                .unwrap_or_else(|| panic!("{helper_name} works only on types!")),
+        )
+    });
+    let vals = handle_operand(&args[0].node, ctx);
+    let ptrs = handle_operand(&args[1].node, ctx);
+    let mask = handle_operand(&args[2].node, ctx);
+    let name = ctx.alloc_string(helper_name);
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    if helper_name == "simd_scatter" {
+        let scatter = main_module.static_mref(&[t_type, u_type, v_type], Type::Void, name, ctx);
+        CILRoot::Call {
+            site: scatter,
+            args: [vals, ptrs, mask].into(),
+        }
+    } else {
+        let gather = main_module.static_mref(&[t_type, u_type, v_type], t_type, name, ctx);
+        place_set(destination, call!(gather, [vals, ptrs, mask]), ctx)
+    }
+}
+/// Implements `simd_reduce_*`, by calling the builtin `helper_name(lanes: *const U, start: usize, count: usize, init: U) -> U`.
+///
+/// The vector is passed by its address, so this also works for vectors with lengths .NET has no vector types for(eg. 1 or 3).
+/// `ordered` reductions start with their second argument as the accumulator, all others start with the first lane.
+pub fn simd_reduce<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    helper_name: &str,
+    ordered: bool,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        if ordered { 2 } else { 1 },
+        "The intrinsic `{helper_name}` got the wrong argument count!"
+    );
+    let vec = call_instance.args[0]
+        .as_type()
+        .unwrap_or_else(|| panic!("{helper_name} works only on types!"));
+    let (count, elem_ty) = vec.simd_size_and_type(ctx.tcx());
+    let elem = ctx.type_from_cache(elem_ty);
+    let lanes = operand_address(&args[0].node, ctx).cast_ptr(ctx.nptr(elem));
+    let (start, init) = if ordered {
+        (0, handle_operand(&args[1].node, ctx))
+    } else {
+        (1, deref_op(PlaceTy::Ty(elem_ty), ctx, lanes.clone()))
+    };
+    let name = ctx.alloc_string(helper_name);
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let lanes_ptr = ctx.nptr(elem);
+    let reduce = main_module.static_mref(
+        &[
+            lanes_ptr,
+            Type::Int(Int::USize),
+            Type::Int(Int::USize),
+            elem,
+        ],
+        elem,
+        name,
+        ctx,
+    );
+    place_set(
+        destination,
+        call!(
+            reduce,
+            [
+                lanes,
+                CILNode::V2(ctx.alloc_node(Const::USize(start))),
+                CILNode::V2(ctx.alloc_node(Const::USize(count))),
+                init
+            ]
+        ),
+        ctx,
+    )
+}
+/// Implements `simd_reduce_all` and `simd_reduce_any`, by calling the builtin with the same name. It uses the move mask of the vector,
+/// so this lowers to eg. `movmskps` + `cmp` on x86. Masks with no .NET vector type(eg. with 3 lanes) get checked lane by lane, trough a pointer.
+pub fn simd_reduce_mask<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    helper_name: &str,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        1,
+        "The intrinsic `{helper_name}` MUST take in exactly 1 argument!"
+    );
+    let mask_ty = call_instance.args[0]
+        .as_type()
+        .unwrap_or_else(|| panic!("{helper_name} works only on types!"));
+    let mask = ctx.type_from_cache(mask_ty);
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    if mask.as_simdvector().is_none() {
+        let (count, elem_ty) = mask_ty.simd_size_and_type(ctx.tcx());
+        let elem = ctx.type_from_cache(elem_ty);
+        let elem_ptr = ctx.nptr(elem);
+        let lanes = operand_address(&args[0].node, ctx).cast_ptr(elem_ptr);
+        let name = ctx.alloc_string(format!("{helper_name}_lanes"));
+        let reduce =
+            main_module.static_mref(&[elem_ptr, Type::Int(Int::USize)], Type::Bool, name, ctx);
+        return place_set(
+            destination,
+            call!(
+                reduce,
+                [lanes, CILNode::V2(ctx.alloc_node(Const::USize(count)))]
+            ),
+            ctx,
+        );
+    }
+    let x = handle_operand(&args[0].node, ctx);
+    let name = ctx.alloc_string(helper_name);
+    let reduce = main_module.static_mref(&[mask], Type::Bool, name, ctx);
+    place_set(destination, call!(reduce, [x]), ctx)
+}
+/// Implements `simd_cast_ptr::<T, U>(ptrs: T) -> U`, which casts a vector of pointers to a vector of differently-typed pointers.
+///
+/// The lanes of pointer vectors are stored as `usize`, so the representation does not change, and the vector only needs to be reinterpreted.
+pub fn simd_cast_ptr<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        1,
+        "The intrinsic `simd_cast_ptr` MUST take in exactly 1 argument!"
+    );
+    let [src, target] = [0, 1].map(|idx| {
+        ctx.type_from_cache(
+            call_instance.args[idx]
+                .as_type()
+                .expect("simd_cast_ptr works only on types!"),
+        )
+    });
+    let ptrs = handle_operand(&args[0].node, ctx);
+    if src == target {
+        return place_set(destination, ptrs, ctx);
+    }
+    place_set(destination, ptrs.transmute_on_stack(src, target, ctx), ctx)
+}
+/// Implements `simd_select::<M, T>(mask: M, if_true: T, if_false: T) -> T`, by calling the builtin with the same name.
+pub fn simd_select<'tcx>(
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    call_instance: Instance<'tcx>,
+    ctx: &mut MethodCompileCtx<'tcx, '_>,
+) -> CILRoot {
+    debug_assert_eq!(
+        args.len(),
+        3,
+        "The intrinsic `simd_select` MUST take in exactly 3 arguments!"
+    );
+    let [m_type, t_type] = [0, 1].map(|idx| {
+        ctx.type_from_cache(
+            call_instance.args[idx]
+                .as_type()
+                .expect("simd_select works only on types!"),
+        )
+    });
+    if m_type.as_simdvector().is_none() || t_type.as_simdvector().is_none() {
+        let [(mask_count, mask_elem), (count, elem)] = [0, 1].map(|idx| {
+            call_instance.args[idx]
+                .as_type()
+                .expect("simd_select works only on types!")
+                .simd_size_and_type(ctx.tcx())
+        });
+        assert_eq!(
+            mask_count, count,
+            "Invalid simd_select in {call_instance:?}: the mask and the values have different lane counts."
+        );
+        let [mask_ptr, elem_ptr] = [mask_elem, elem].map(|elem| {
+            let elem = ctx.type_from_cache(elem);
+            ctx.nptr(elem)
+        });
+        let dst = place_adress(destination, ctx);
+        let [mask, if_true, if_false] = [0, 1, 2].map(|arg| operand_address(&args[arg].node, ctx));
+        return call_lanes_helper(
+            "simd_select",
+            [
+                (dst, elem_ptr),
+                (mask, mask_ptr),
+                (if_true, elem_ptr),
+                (if_false, elem_ptr),
+            ],
+            count,
+            ctx,
+        );
+    }
+    if let Err(err) = cilly::v2::builtins::simd::validate_select(m_type, t_type) {
+        panic!("Invalid simd_select in {call_instance:?}: {err}");
+    }
+    let mask = handle_operand(&args[0].node, ctx);
+    let if_true = handle_operand(&args[1].node, ctx);
+    let if_false = handle_operand(&args[2].node, ctx);
+    let name = ctx.alloc_string("simd_select");
+    let main_module = ctx.main_module();
+    let main_module = ctx[*main_module].clone();
+    let select = main_module.static_mref(&[m_type, t_type, t_type], t_type, name, ctx);
+    place_set(destination, call!(select, [mask, if_true, if_false]), ctx)
+}
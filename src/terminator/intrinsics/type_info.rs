@@ -21,8 +21,10 @@ pub fn is_val_statically_known<'tcx>(
         1,
         "The intrinsic `is_val_statically_known` MUST take in exactly 1 argument!"
     );
-    // assert_eq!(args.len(),1,"The intrinsic `unlikely` MUST take in exactly 1 argument!");
-    place_set(destination, CILNode::V2(ctx.alloc_node(false)), ctx)
+    // Only MIR constants are known at compile time. This works the same for integers, floats, bools and pointers.
+    // Returning `false` is always correct, so any value computed at runtime is treated as unknown.
+    let is_known = matches!(args[0].node, Operand::Constant(_));
+    place_set(destination, CILNode::V2(ctx.alloc_node(is_known)), ctx)
 }
 pub fn size_of_val<'tcx>(
     args: &[Spanned<Operand<'tcx>>],
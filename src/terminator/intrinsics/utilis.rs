@@ -30,6 +30,7 @@ pub fn atomic_add(addr: CILNode, addend: CILNode, tpe: Type, asm: &mut Assembly)
                 MethodKind::Static,
                 vec![].into(),
             );
+            // Returns the previous address, so it must be cast back to a pointer.
             call!(
                 asm.alloc_methodref(mref),
                 [
@@ -37,6 +38,7 @@ pub fn atomic_add(addr: CILNode, addend: CILNode, tpe: Type, asm: &mut Assembly)
                     addend.cast_ptr(Type::Int(Int::USize))
                 ]
             )
+            .cast_ptr(tpe)
         }
 
         _ => todo!(),
@@ -1 +1,14 @@
-
+use cilly::{cil_node::CILNode, v2::Assembly, Int, Type};
+/// Reinterprets `val` of type `src` as a value of type `dst`. Used by both `transmute` and `transmute_unchecked`: the size of the types is not checked here,
+/// since rustc checks it for `transmute`, and the caller guarantees it for `transmute_unchecked`.
+pub fn transmute(src: Type, dst: Type, val: CILNode, asm: &mut Assembly) -> CILNode {
+    match (&src, &dst) {
+        // Pointer-sized values(including function pointers, eg. `fn()` to `*const ()` or `usize`) only need a `PtrCast`, and not a copy trough memory.
+        (
+            Type::Int(Int::ISize | Int::USize) | Type::Ptr(_) | Type::FnPtr(_),
+            Type::Int(Int::ISize | Int::USize) | Type::Ptr(_) | Type::FnPtr(_),
+        ) => val.cast_ptr(dst),
+        (Type::Int(Int::U16), Type::PlatformChar) => val,
+        (_, _) => val.transmute_on_stack(src, dst, asm),
+    }
+}
@@ -209,10 +209,19 @@ pub fn get_type<'tcx>(ty: Ty<'tcx>, ctx: &mut MethodCompileCtx<'tcx, '_>) -> Typ
                 if count == 1 {
                     return elem;
                 }
-                return Type::SIMDVector(SIMDVector::new(
-                    elem.try_into().unwrap(),
-                    count.try_into().unwrap(),
-                ));
+                // Vectors of pointers(used by eg. `simd_gather`) store their lanes as `usize`.
+                let elem = match elem {
+                    Type::Ptr(_) | Type::FnPtr(_) => Type::Int(Int::USize),
+                    _ => elem,
+                };
+                // Vectors with no .NET equivalent(eg. with 3 lanes) are translated like any other struct. So are vectors whose Rust size differs from
+                // the size of the .NET vector, since `size_of` uses the .NET size, while field offsets use the Rust layout.
+                if let Some(vec) =
+                    SIMDVector::try_new(elem.try_into().unwrap(), count.try_into().unwrap())
+                        .filter(|vec| ctx.layout_of(ty).size.bytes() == u64::from(vec.bits() / 8))
+                {
+                    return Type::SIMDVector(vec);
+                }
             }
             if is_name_magic(name.as_ref()) {
                 if name.contains(INTEROP_CLASS_TPE_NAME) {
@@ -0,0 +1,31 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+use core::cmp::Ordering;
+include!("../common.rs");
+fn main() {
+    let neg_zero = black_box(-0.0_f64);
+    let zero = black_box(0.0_f64);
+    let nan = black_box(f64::NAN);
+    let neg_nan = black_box(-f64::NAN);
+    // Signed zeroes are ordered, even though they compare equal.
+    test_eq!(neg_zero.total_cmp(&zero), Ordering::Less);
+    test_eq!(zero.total_cmp(&neg_zero), Ordering::Greater);
+    test_eq!(zero.total_cmp(&zero), Ordering::Equal);
+    // Positive NaN is bigger than everything else, negative NaN is smaller than everything else.
+    test_eq!(nan.total_cmp(&f64::INFINITY), Ordering::Greater);
+    test_eq!(nan.total_cmp(&nan), Ordering::Equal);
+    test_eq!(neg_nan.total_cmp(&f64::NEG_INFINITY), Ordering::Less);
+    test_eq!(neg_nan.total_cmp(&nan), Ordering::Less);
+    test_eq!(black_box(-1.5_f64).total_cmp(&black_box(-2.5)), Ordering::Greater);
+    test_eq!(black_box(1.5_f64).total_cmp(&black_box(2.5)), Ordering::Less);
+    test_eq!(black_box(-0.0_f32).total_cmp(&black_box(0.0)), Ordering::Less);
+    test_eq!(black_box(f32::NAN).total_cmp(&black_box(f32::INFINITY)), Ordering::Greater);
+}
@@ -0,0 +1,32 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+fn main() {
+    // `bool` and `char` are unsigned, so they must be zero-extended.
+    let t: bool = black_box(true);
+    test_eq!(t as u8, 1_u8);
+    test_eq!(t as i8, 1_i8);
+    test_eq!(t as i64, 1_i64);
+    test_eq!(t as isize, 1_isize);
+    let f: bool = black_box(false);
+    test_eq!(f as u64, 0_u64);
+    let byte: u8 = black_box(0xE9);
+    let c = byte as char;
+    test_eq!(c as u32, 0xE9_u32);
+    test_eq!(c as i64, 0xE9_i64);
+    let c: char = black_box('\u{10FFFF}');
+    test_eq!(c as u32, 0x10FFFF_u32);
+    test_eq!(c as u64, 0x10FFFF_u64);
+    test_eq!(c as i16, -1_i16);
+    test_eq!(c as u8, 0xFF_u8);
+    let c = char::from_u32(black_box(0x1F600_u32));
+    test!(c.is_some());
+}
@@ -0,0 +1,21 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+fn main() {
+    // Casts which don't lose information are unaffected by the checks.
+    let small: i64 = black_box(-7);
+    test_eq!(small as i32, -7_i32);
+    let byte: u32 = black_box(255);
+    test_eq!(byte as u8, 255_u8);
+    // With `CHECKED_INT_CASTS`, this truncating cast throws.
+    let big: i64 = black_box(i64::MAX);
+    black_box(big as i32);
+}
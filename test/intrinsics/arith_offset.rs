@@ -9,6 +9,7 @@
 #![allow(internal_features, incomplete_features, unused_variables, dead_code)]
 #![no_std]
 include!("../common.rs");
+struct Kilobyte([u8; 1024]);
 fn main() {
     let mut a = [0, 1, 2, 3, 4, 5, 6, 7];
     let aptr = &mut a as *mut i32;
@@ -16,4 +17,21 @@ fn main() {
     test_eq!(unsafe { *aptr.wrapping_offset(1) }, 1);
     test_eq!(unsafe { *aptr.wrapping_offset(5) }, 5);
     test_eq!(unsafe { *aptr.wrapping_offset(7) }, 7);
+    // Negative offsets move the pointer backwards.
+    let end = black_box(aptr.wrapping_offset(7));
+    test_eq!(unsafe { *end.wrapping_offset(black_box(-7)) }, 0);
+    test_eq!(unsafe { *end.wrapping_offset(black_box(-2)) }, 5);
+    // Large elements get scaled by their size.
+    let mut kbs = [Kilobyte([0; 1024]), Kilobyte([1; 1024]), Kilobyte([2; 1024])];
+    let kb_ptr = black_box(&mut kbs as *mut Kilobyte);
+    let last = kb_ptr.wrapping_offset(black_box(2));
+    test_eq!(last as usize - kb_ptr as usize, 2048);
+    test_eq!(unsafe { (*last).0[1023] }, 2);
+    test_eq!(unsafe { (*last.wrapping_offset(black_box(-1))).0[0] }, 1);
+    // Offsets, whose size in bytes overflows `isize`, wrap around.
+    let wrapped = kb_ptr.wrapping_offset(black_box(isize::MAX / 512));
+    test_eq!(
+        wrapped as usize,
+        (kb_ptr as usize).wrapping_add(((isize::MAX / 512) as usize).wrapping_mul(1024))
+    );
 }
@@ -77,6 +77,16 @@ fn main() {
     let atomic_old = atomic.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
     unsafe { printf(c"atomic_old:%lx\n".as_ptr(), atomic_old as u64) };
     test_eq!(atomic_old, 0);
+    // `fetch_add` and `fetch_sub` return the value from before the operation.
+    let atomic = core::sync::atomic::AtomicUsize::new(black_box(usize::MAX - 1));
+    test_eq!(atomic.fetch_add(black_box(5), SeqCst), usize::MAX - 1);
+    test_eq!(atomic.load(SeqCst), 3);
+    test_eq!(atomic.fetch_sub(black_box(2), SeqCst), 3);
+    test_eq!(atomic.load(SeqCst), 1);
+    let mut data = [0_u64; 4];
+    let atom = AtomicPtr::new(data.as_mut_ptr());
+    test_eq!(atom.fetch_byte_add(16, SeqCst), data.as_mut_ptr());
+    test_eq!(atom.load(SeqCst), data.as_mut_ptr().wrapping_add(2));
 }
 fn ptr_bitops_tagging() {
     #[repr(align(16))]
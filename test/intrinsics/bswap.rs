@@ -49,5 +49,27 @@ fn main() {
         black_box(0xEF_CD_AB_89_67_45_23_01u64),
         core::intrinsics::bswap(black_box(0x01_23_45_67_89_AB_CD_EFu64))
     );
+    // The results keep the width of the argument, so the swapped bytes don't spill into wider integers.
+    let swapped: u8 = core::intrinsics::bswap(black_box(0xA5_u8));
+    test_eq!(swapped, black_box(0xA5_u8));
+    let swapped: u16 = core::intrinsics::bswap(black_box(0xFF_01_u16));
+    test_eq!(swapped, black_box(0x01_FF_u16));
+    test_eq!(swapped.wrapping_add(black_box(0xFE_01)), black_box(0));
+    let swapped: i16 = core::intrinsics::bswap(black_box(0x00_80_i16));
+    test_eq!(swapped, black_box(i16::MIN));
+    let swapped: u32 = core::intrinsics::bswap(black_box(0xFF_00_00_01_u32));
+    test_eq!(swapped, black_box(0x01_00_00_FF_u32));
+    test_eq!(swapped >> 24, black_box(1));
+    let reversed: u8 = core::intrinsics::bitreverse(black_box(0b0000_0001_u8));
+    test_eq!(reversed, black_box(0b1000_0000_u8));
+    test_eq!(reversed.wrapping_mul(black_box(2)), black_box(0));
+    let reversed: u16 = core::intrinsics::bitreverse(black_box(0x00_01_u16));
+    test_eq!(reversed, black_box(0x80_00_u16));
+    test_eq!(reversed.wrapping_mul(black_box(2)), black_box(0));
+    let reversed: i8 = core::intrinsics::bitreverse(black_box(1_i8));
+    test_eq!(reversed, black_box(i8::MIN));
+    let reversed: u32 = core::intrinsics::bitreverse(black_box(0x0000_0003_u32));
+    test_eq!(reversed, black_box(0xC000_0000_u32));
+    test_eq!(reversed.wrapping_mul(black_box(4)), black_box(0));
     //test_eq!(black_box(0xEF_CD_AB_89_67_45_23_01_EF_CD_AB_89_67_45_23_01u128),core::intrinsics::bswap(black_box(0x01_23_45_67_89_AB_CD_EF_01_23_45_67_89_AB_CD_EFu128)));
 }
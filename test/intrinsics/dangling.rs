@@ -0,0 +1,22 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+use core::ptr::NonNull;
+#[repr(align(32))]
+struct Aligned32(u8);
+fn main() {
+    // Dangling pointers have an address equal to the aligement of their type.
+    test_eq!(black_box(NonNull::<u64>::dangling()).as_ptr() as usize, 8);
+    test_eq!(black_box(NonNull::<u8>::dangling()).as_ptr() as usize, 1);
+    test_eq!(black_box(NonNull::<Aligned32>::dangling()).as_ptr() as usize, 32);
+    test_eq!(black_box(core::ptr::dangling::<u32>()) as usize, 4);
+    test_eq!(black_box(core::ptr::dangling_mut::<u16>()) as usize, 2);
+}
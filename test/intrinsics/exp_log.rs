@@ -0,0 +1,87 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::exp2f32;
+use core::intrinsics::exp2f64;
+use core::intrinsics::expf32;
+use core::intrinsics::expf64;
+use core::intrinsics::logf32;
+use core::intrinsics::log10f32;
+use core::intrinsics::log10f64;
+use core::intrinsics::log2f32;
+use core::intrinsics::log2f64;
+use core::intrinsics::logf64;
+
+use core::intrinsics::fabsf32;
+use core::intrinsics::fabsf64;
+
+fn main() {
+    test_eq!(unsafe { expf32(black_box(0.0)) }, black_box(1.0));
+    let abs_difference = unsafe { fabsf32(expf32(black_box(1.0)) - core::f32::consts::E) };
+    test!(abs_difference <= black_box(f32::EPSILON));
+    test_eq!(unsafe { expf32(f32::NEG_INFINITY) }, black_box(0.0));
+    test_eq!(unsafe { expf64(black_box(0.0)) }, black_box(1.0));
+    let abs_difference = unsafe { fabsf64(expf64(black_box(1.0)) - core::f64::consts::E) };
+    test!(abs_difference <= black_box(f64::EPSILON));
+    test_eq!(unsafe { expf64(f64::NEG_INFINITY) }, black_box(0.0));
+    // `expf32` and `expf64` must both go to the `exp` of their own float class.
+    let abs_difference = unsafe { fabsf32(expf32(core::f32::consts::LN_2) - 2.0) };
+    test!(abs_difference <= black_box(2.0 * f32::EPSILON));
+    let abs_difference = unsafe { fabsf64(expf64(core::f64::consts::LN_2) - 2.0) };
+    test!(abs_difference <= black_box(2.0 * f64::EPSILON));
+    test_eq!(unsafe { expf32(f32::INFINITY) }, black_box(f32::INFINITY));
+    test_eq!(unsafe { expf64(f64::INFINITY) }, black_box(f64::INFINITY));
+    // 100.0 overflows `f32`, but not `f64`.
+    test_eq!(unsafe { expf32(black_box(100.0)) }, black_box(f32::INFINITY));
+    test!(unsafe { expf64(black_box(100.0)) } < black_box(f64::INFINITY));
+    test!(unsafe { expf32(black_box(f32::NAN)) }.is_nan());
+    test!(unsafe { expf64(black_box(f64::NAN)) }.is_nan());
+
+    let abs_difference = unsafe { fabsf32(logf32(core::f32::consts::E) - 1.0) };
+    test!(abs_difference <= black_box(f32::EPSILON));
+    let abs_difference = unsafe { fabsf64(logf64(core::f64::consts::E) - 1.0) };
+    test!(abs_difference <= black_box(f64::EPSILON));
+
+    // Integer exponents should produce exact powers of two.
+    test_eq!(unsafe { exp2f64(black_box(10.0)) }, black_box(1024.0));
+    test_eq!(unsafe { exp2f64(black_box(0.0)) }, black_box(1.0));
+    test_eq!(unsafe { exp2f64(black_box(-3.0)) }, black_box(0.125));
+    test_eq!(unsafe { exp2f64(black_box(52.0)) }, black_box(4503599627370496.0));
+    test_eq!(unsafe { exp2f64(f64::NEG_INFINITY) }, black_box(0.0));
+    test_eq!(unsafe { exp2f32(black_box(10.0)) }, black_box(1024.0));
+    test_eq!(unsafe { exp2f32(black_box(-1.0)) }, black_box(0.5));
+    test_eq!(unsafe { exp2f32(black_box(23.0)) }, black_box(8388608.0));
+
+    // Logarithms of exact powers should be exact.
+    test_eq!(unsafe { log2f32(black_box(8.0)) }, black_box(3.0));
+    test_eq!(unsafe { log2f32(black_box(1.0)) }, black_box(0.0));
+    test_eq!(unsafe { log2f32(black_box(0.25)) }, black_box(-2.0));
+    test_eq!(unsafe { log2f32(black_box(1024.0)) }, black_box(10.0));
+    test_eq!(unsafe { log2f64(black_box(8.0)) }, black_box(3.0));
+    test_eq!(unsafe { log2f64(black_box(0.125)) }, black_box(-3.0));
+    test_eq!(unsafe { log2f64(black_box(4503599627370496.0)) }, black_box(52.0));
+    test_eq!(unsafe { log10f32(black_box(1000.0)) }, black_box(3.0));
+    test_eq!(unsafe { log10f32(black_box(1.0)) }, black_box(0.0));
+    test_eq!(unsafe { log10f32(black_box(100.0)) }, black_box(2.0));
+    test_eq!(unsafe { log10f64(black_box(1000.0)) }, black_box(3.0));
+    test_eq!(unsafe { log10f64(black_box(1e10)) }, black_box(10.0));
+    test_eq!(unsafe { log10f64(black_box(0.01)) }, black_box(-2.0));
+
+    // log(0) is -inf, and log of a negative number is NaN.
+    test_eq!(unsafe { log2f32(black_box(0.0)) }, black_box(f32::NEG_INFINITY));
+    test_eq!(unsafe { log2f64(black_box(0.0)) }, black_box(f64::NEG_INFINITY));
+    test_eq!(unsafe { log10f64(black_box(-0.0)) }, black_box(f64::NEG_INFINITY));
+    test!(unsafe { log2f32(black_box(-1.0)) }.is_nan());
+    test!(unsafe { log2f64(black_box(-1.0)) }.is_nan());
+    test!(unsafe { log10f32(black_box(-1.0)) }.is_nan());
+}
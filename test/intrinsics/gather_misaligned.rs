@@ -0,0 +1,26 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params,
+    portable_simd
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+use core::intrinsics::simd::simd_gather;
+use core::simd::Simd;
+fn main() {
+    let data = [10_i32, 20, 30, 40];
+    let base = black_box(&data as *const i32);
+    // The pointer of lane 2 is active, but points one byte into an `i32`.
+    let misaligned = base.cast::<u8>().wrapping_add(1).cast::<i32>();
+    let ptrs: Simd<*const i32, 4> =
+        Simd::from_array([base, base.wrapping_add(1), misaligned, base.wrapping_add(3)]);
+    let mask: Simd<i32, 4> = black_box(Simd::from_array([-1, -1, -1, -1]));
+    let vals: Simd<i32, 4> = black_box(Simd::from_array([1, 2, 3, 4]));
+    // With `SIMD_PTR_CHECKS`, this gather traps.
+    black_box(unsafe { simd_gather(vals, ptrs, mask) });
+}
@@ -0,0 +1,107 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::maximumf32;
+use core::intrinsics::maximumf64;
+use core::intrinsics::maxnumf32;
+use core::intrinsics::maxnumf64;
+use core::intrinsics::minimumf32;
+use core::intrinsics::minimumf64;
+use core::intrinsics::minnumf32;
+use core::intrinsics::minnumf64;
+
+fn main() {
+    // If one operand is NaN, the other one is returned.
+    test_eq!(
+        unsafe { minnumf32(black_box(f32::NAN), black_box(1.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { minnumf32(black_box(1.0), black_box(f32::NAN)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { maxnumf32(black_box(f32::NAN), black_box(1.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { maxnumf32(black_box(1.0), black_box(f32::NAN)) },
+        black_box(1.0)
+    );
+    test!(unsafe { minnumf32(black_box(f32::NAN), black_box(f32::NAN)) }.is_nan());
+    test_eq!(
+        unsafe { minnumf64(black_box(f64::NAN), black_box(1.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { minnumf64(black_box(1.0), black_box(f64::NAN)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { maxnumf64(black_box(f64::NAN), black_box(1.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { maxnumf64(black_box(1.0), black_box(f64::NAN)) },
+        black_box(1.0)
+    );
+    test!(unsafe { maxnumf64(black_box(f64::NAN), black_box(f64::NAN)) }.is_nan());
+    // -0.0 is less than +0.0
+    test_eq!(
+        unsafe { minnumf32(black_box(-0.0), black_box(0.0)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { maxnumf32(black_box(-0.0), black_box(0.0)) }.to_bits(),
+        black_box(0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { minnumf64(black_box(0.0), black_box(-0.0)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    test_eq!(
+        unsafe { maxnumf64(black_box(0.0), black_box(-0.0)) }.to_bits(),
+        black_box(0.0_f64).to_bits()
+    );
+    // minimum and maximum propagate NaNs.
+    test!(unsafe { minimumf32(black_box(f32::NAN), black_box(1.0)) }.is_nan());
+    test!(unsafe { minimumf32(black_box(1.0), black_box(f32::NAN)) }.is_nan());
+    test!(unsafe { maximumf32(black_box(f32::NAN), black_box(1.0)) }.is_nan());
+    test!(unsafe { maximumf64(black_box(1.0), black_box(f64::NAN)) }.is_nan());
+    test!(unsafe { minimumf64(black_box(f64::NAN), black_box(1.0)) }.is_nan());
+    test_eq!(
+        unsafe { minimumf32(black_box(-1.0), black_box(1.0)) },
+        black_box(-1.0)
+    );
+    test_eq!(
+        unsafe { maximumf64(black_box(-1.0), black_box(1.0)) },
+        black_box(1.0)
+    );
+    // -0.0 is less than +0.0
+    test_eq!(
+        unsafe { minimumf32(black_box(0.0), black_box(-0.0)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { maximumf32(black_box(-0.0), black_box(0.0)) }.to_bits(),
+        black_box(0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { minimumf64(black_box(-0.0), black_box(0.0)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    test_eq!(
+        unsafe { maximumf64(black_box(0.0), black_box(-0.0)) }.to_bits(),
+        black_box(0.0_f64).to_bits()
+    );
+}
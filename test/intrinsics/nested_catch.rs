@@ -0,0 +1,49 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+use core::intrinsics::catch_unwind;
+extern "C-unwind" {
+    fn _Unwind_RaiseException(payload: *mut u8) -> i32;
+}
+static mut INNER_CAUGHT: u32 = 0;
+static mut OUTER_CAUGHT: u32 = 0;
+const PAYLOAD: usize = 0x1234;
+fn throw(_data: *mut u8) {
+    unsafe { _Unwind_RaiseException(black_box(PAYLOAD as *mut u8)) };
+}
+fn inner_catch(data: *mut u8, payload: *mut u8) {
+    unsafe {
+        INNER_CAUGHT += 1;
+        *(data as *mut usize) = payload as usize;
+    }
+}
+fn outer_try(data: *mut u8) {
+    // The inner `catch_unwind` catches the panic, so it must not reach the outer handler.
+    let res = unsafe { catch_unwind(throw, data, inner_catch) };
+    test_eq!(res, 1);
+}
+fn outer_catch(_data: *mut u8, _payload: *mut u8) {
+    unsafe { OUTER_CAUGHT += 1 };
+}
+fn main() {
+    let mut payload = 0_usize;
+    let res = unsafe {
+        catch_unwind(
+            outer_try,
+            &mut payload as *mut usize as *mut u8,
+            outer_catch,
+        )
+    };
+    test_eq!(res, 0);
+    test_eq!(payload, PAYLOAD);
+    test_eq!(unsafe { INNER_CAUGHT }, 1);
+    test_eq!(unsafe { OUTER_CAUGHT }, 0);
+}
@@ -32,6 +32,8 @@ fn main() {
     test_eq!(unsafe { sqrtf32(positive) }, black_box(2.0));
     test!(unsafe { sqrtf32(negative) }.is_nan());
     test_eq!(unsafe { sqrtf32(negative_zero) }, black_box(negative_zero));
+    test_eq!(unsafe { sqrtf32(black_box(2.25)) }, black_box(1.5));
+    test_eq!(unsafe { sqrtf32(f32::INFINITY) }, black_box(f32::INFINITY));
 
     let positive = 4.0_f64;
     let negative = -4.0_f64;
@@ -53,6 +55,17 @@ fn main() {
     let x = 2.0_f64;
     let abs_difference = unsafe { fabsf64(powif64(x, 2) - (x * x)) };
     test!(abs_difference <= black_box(f64::EPSILON));
+    // x^0 is always 1, even for NaN.
+    test_eq!(unsafe { powif32(black_box(3.5), 0) }, black_box(1.0));
+    test_eq!(unsafe { powif32(f32::NAN, 0) }, black_box(1.0));
+    test_eq!(unsafe { powif64(black_box(-3.5), 0) }, black_box(1.0));
+    test_eq!(unsafe { powif64(f64::NAN, 0) }, black_box(1.0));
+    test_eq!(unsafe { powif32(black_box(-3.0), 3) }, black_box(-27.0));
+    test_eq!(unsafe { powif64(black_box(1.5), 2) }, black_box(2.25));
+    // Large and non-constant exponents go through `Pow`.
+    test_eq!(unsafe { powif32(black_box(2.0), 30) }, black_box(1073741824.0));
+    test_eq!(unsafe { powif64(black_box(2.0), 60) }, black_box(1152921504606846976.0));
+    test_eq!(unsafe { powif64(black_box(2.0), black_box(-2)) }, black_box(0.25));
 
     let f = 2.0f32;
     // 2^2 - 4 == 0
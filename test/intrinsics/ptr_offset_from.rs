@@ -0,0 +1,36 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+struct Zst;
+fn main() {
+    let a = [0_u32, 1, 2, 3, 4, 5, 6, 7];
+    let start = black_box(&a as *const u32);
+    let end = black_box(unsafe { start.add(5) });
+    test_eq!(unsafe { end.offset_from(start) }, 5);
+    test_eq!(unsafe { start.offset_from(end) }, -5);
+    // Identical pointers are always 0 elements apart.
+    test_eq!(unsafe { start.offset_from(black_box(start)) }, 0);
+    test_eq!(
+        unsafe { core::intrinsics::ptr_offset_from(end, black_box(end)) },
+        0
+    );
+    // Even if the elements are ZSTs.
+    let zst = black_box(&Zst as *const Zst);
+    test_eq!(
+        unsafe { core::intrinsics::ptr_offset_from(zst, black_box(zst)) },
+        0
+    );
+    let unit = black_box(&() as *const ());
+    test_eq!(
+        unsafe { core::intrinsics::ptr_offset_from(unit, black_box(unit)) },
+        0
+    );
+}
@@ -95,6 +95,31 @@ fn main() {
     test_eq!(unsafe { roundf64(h) }, black_box(-4.0));
     test_eq!(unsafe { roundf64(i) }, black_box(4.0));
     test_eq!(unsafe { roundf64(j) }, black_box(5.0));
+    // Values rounding to zero keep their sign.
+    test_eq!(
+        unsafe { roundf32(black_box(-0.3)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { roundf32(black_box(-0.0)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { roundf32(black_box(0.3)) }.to_bits(),
+        black_box(0.0_f32).to_bits()
+    );
+    test_eq!(
+        unsafe { roundf64(black_box(-0.3)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    test_eq!(
+        unsafe { roundf64(black_box(-0.0)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    test_eq!(
+        unsafe { roundf64(black_box(0.3)) }.to_bits(),
+        black_box(0.0_f64).to_bits()
+    );
     let f = 3.01_f32;
     let g = 4.0_f32;
     test_eq!(unsafe { ceilf32(f) }, black_box(4.0));
@@ -127,4 +152,25 @@ fn main() {
     assert_eq!(unsafe { truncf64(f) }, black_box(3.0));
     assert_eq!(unsafe { truncf64(g) }, black_box(3.0));
     assert_eq!(unsafe { truncf64(h) }, black_box(-3.0));
+    // Edge cases: the sign of zero is kept, infinities and NaNs are passed through.
+    test_eq!(
+        unsafe { truncf32(black_box(-0.5)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test_eq!(unsafe { truncf32(f32::INFINITY) }, black_box(f32::INFINITY));
+    test_eq!(
+        unsafe { truncf32(f32::NEG_INFINITY) },
+        black_box(f32::NEG_INFINITY)
+    );
+    test!(unsafe { truncf32(black_box(f32::NAN)) }.is_nan());
+    test_eq!(
+        unsafe { truncf64(black_box(-0.5)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    test_eq!(unsafe { truncf64(f64::INFINITY) }, black_box(f64::INFINITY));
+    test_eq!(
+        unsafe { truncf64(f64::NEG_INFINITY) },
+        black_box(f64::NEG_INFINITY)
+    );
+    test!(unsafe { truncf64(black_box(f64::NAN)) }.is_nan());
 }
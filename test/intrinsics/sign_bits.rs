@@ -0,0 +1,69 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::copysignf32;
+use core::intrinsics::copysignf64;
+use core::intrinsics::fabsf32;
+use core::intrinsics::fabsf64;
+
+fn main() {
+    // fabs only clears the sign bit.
+    test_eq!(unsafe { fabsf32(black_box(-2.5)) }, black_box(2.5));
+    test_eq!(
+        unsafe { fabsf32(black_box(-0.0)) }.to_bits(),
+        black_box(0.0_f32).to_bits()
+    );
+    let nan = unsafe { fabsf32(black_box(-f32::NAN)) };
+    test!(nan.is_nan());
+    test!(nan.is_sign_positive());
+    test_eq!(unsafe { fabsf64(black_box(-2.5)) }, black_box(2.5));
+    test_eq!(
+        unsafe { fabsf64(black_box(-0.0)) }.to_bits(),
+        black_box(0.0_f64).to_bits()
+    );
+    let nan = unsafe { fabsf64(black_box(-f64::NAN)) };
+    test!(nan.is_nan());
+    test!(nan.is_sign_positive());
+    // copysign only transfers the sign bit.
+    test_eq!(
+        unsafe { copysignf32(black_box(1.0), black_box(-0.0)) },
+        black_box(-1.0)
+    );
+    test_eq!(
+        unsafe { copysignf32(black_box(-1.0), black_box(0.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { copysignf32(black_box(0.0), black_box(-3.0)) }.to_bits(),
+        black_box(-0.0_f32).to_bits()
+    );
+    test!(unsafe { copysignf32(black_box(1.0), black_box(-f32::NAN)) } == black_box(-1.0));
+    let nan = unsafe { copysignf32(black_box(f32::NAN), black_box(-1.0)) };
+    test!(nan.is_nan());
+    test!(nan.is_sign_negative());
+    test_eq!(
+        unsafe { copysignf64(black_box(1.0), black_box(-0.0)) },
+        black_box(-1.0)
+    );
+    test_eq!(
+        unsafe { copysignf64(black_box(-1.0), black_box(0.0)) },
+        black_box(1.0)
+    );
+    test_eq!(
+        unsafe { copysignf64(black_box(0.0), black_box(-3.0)) }.to_bits(),
+        black_box(-0.0_f64).to_bits()
+    );
+    let nan = unsafe { copysignf64(black_box(f64::NAN), black_box(-1.0)) };
+    test!(nan.is_nan());
+    test!(nan.is_sign_negative());
+}
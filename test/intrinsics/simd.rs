@@ -6,12 +6,33 @@
     start,
     ptr_metadata,
     unsized_const_params,
-    portable_simd
+    portable_simd,
+    repr_simd
 )]
 #![allow(internal_features, incomplete_features, unused_variables, dead_code)]
 #![no_std]
 include!("../common.rs");
-use core::simd::Simd;
+use core::intrinsics::simd::{
+    simd_add, simd_cast_ptr, simd_ceil, simd_eq, simd_fabs, simd_floor, simd_fsqrt, simd_gather,
+    simd_ne, simd_neg, simd_reduce_add_ordered, simd_reduce_add_unordered, simd_reduce_all,
+    simd_reduce_any, simd_reduce_max, simd_reduce_min, simd_scatter, simd_select,
+};
+use core::simd::{cmp::SimdPartialEq, simd_swizzle, LaneCount, Simd, SupportedLaneCount};
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct I32x3([i32; 3]);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct F32x3([f32; 3]);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct I32x1([i32; 1]);
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct I32x4([i32; 4]);
+#[repr(align(16))]
+#[derive(Clone, Copy)]
+struct Aligned([f32; 4]);
 fn main() {
     test_eq!(
         black_box(Simd::from_array([4, 6, 8, 10])),
@@ -20,7 +41,237 @@ fn main() {
     let a = Simd::from_array([0, 1, 2, 3]);
     let b = Simd::from_array([4, 5, 6, 7]);
     test_eq!(a + b, Simd::from_array([4, 6, 8, 10]));
+    // The `simd_add` helper is generated on first use.
+    let sum: Simd<i32, 4> = unsafe { simd_add(black_box(a), black_box(b)) };
+    test_eq!(sum, Simd::from_array([4, 6, 8, 10]));
     let a = Simd::from_array([4, 5, 6, 7]);
     let b = Simd::from_array([0, 1, 2, 3]);
     test_eq!(a - b, Simd::from_array([4, 4, 4, 4]));
+    let a: Simd<u32, 4> = black_box(Simd::from_array([0b0011, 0b0101, 0, 0xF0]));
+    let b: Simd<u32, 4> = black_box(Simd::from_array([0b0101, 0b0101, 0, 0x0F]));
+    test_eq!(a | b, Simd::from_array([0b0111, 0b0101, 0, 0xFF]));
+    test_eq!(a & b, Simd::from_array([0b0001, 0b0101, 0, 0]));
+    test_eq!(a ^ b, Simd::from_array([0b0110, 0, 0, 0xFF]));
+    // Integer SIMD arithmetic wraps on overflow.
+    let a: Simd<u8, 16> = black_box(Simd::splat(250));
+    let b: Simd<u8, 16> = black_box(Simd::from_array([
+        0, 1, 5, 6, 7, 10, 100, 255, 0, 1, 5, 6, 7, 10, 100, 255,
+    ]));
+    test_eq!(
+        a + b,
+        Simd::from_array([250, 251, 255, 0, 1, 4, 94, 249, 250, 251, 255, 0, 1, 4, 94, 249])
+    );
+    test_eq!(
+        b - a,
+        Simd::from_array([6, 7, 11, 12, 13, 16, 106, 5, 6, 7, 11, 12, 13, 16, 106, 5])
+    );
+    test_eq!(a * Simd::splat(2), Simd::splat(244));
+    let a: Simd<f32, 4> = black_box(Simd::from_array([1.0, 4.0, 9.0, -8.0]));
+    let b: Simd<f32, 4> = black_box(Simd::from_array([2.0, 2.0, 3.0, 4.0]));
+    test_eq!(a * b, Simd::from_array([2.0, 8.0, 27.0, -32.0]));
+    test_eq!(a / b, Simd::from_array([0.5, 2.0, 3.0, -2.0]));
+    test_eq!(-a, Simd::from_array([-1.0, -4.0, -9.0, 8.0]));
+    test_eq!(
+        unsafe { simd_neg(black_box(Simd::from_array([1, -2, 3, i32::MAX]))) },
+        Simd::from_array([-1, 2, -3, -i32::MAX])
+    );
+    test_eq!(
+        unsafe { simd_fabs(a) },
+        Simd::from_array([1.0, 4.0, 9.0, 8.0])
+    );
+    test_eq!(
+        unsafe { simd_fsqrt(black_box(Simd::from_array([1.0_f32, 4.0, 9.0, 2.25]))) },
+        Simd::from_array([1.0, 2.0, 3.0, 1.5])
+    );
+    let c: Simd<f64, 2> = black_box(Simd::from_array([1.5, -1.5]));
+    test_eq!(unsafe { simd_ceil(c) }, Simd::from_array([2.0, -1.0]));
+    test_eq!(unsafe { simd_floor(c) }, Simd::from_array([1.0, -2.0]));
+    // Gathers only load the active lanes, and keep the rest of the values.
+    let mut data = [10_i32, 20, 30, 40];
+    let base = black_box(&mut data as *mut i32);
+    let ptrs: Simd<*mut i32, 4> = Simd::from_array([
+        base.wrapping_add(3),
+        base,
+        core::ptr::null_mut(),
+        base.wrapping_add(1),
+    ]);
+    let mask: Simd<i32, 4> = black_box(Simd::from_array([-1, -1, 0, -1]));
+    let vals: Simd<i32, 4> = black_box(Simd::from_array([1, 2, 3, 4]));
+    test_eq!(
+        unsafe { simd_gather(vals, ptrs, mask) },
+        Simd::from_array([40, 10, 3, 20])
+    );
+    // Scatters only store the active lanes.
+    unsafe { simd_scatter(vals, ptrs, mask) };
+    test_eq!(data, [2, 4, 30, 1]);
+    // Selects take the lanes with a non-zero mask from the first vector, and the rest from the second one.
+    let mask: Simd<i32, 4> = black_box(Simd::from_array([-1, 0, 0, -1]));
+    let a: Simd<f32, 4> = black_box(Simd::from_array([1.0, 2.0, 3.0, 4.0]));
+    let b: Simd<f32, 4> = black_box(Simd::from_array([-1.0, -2.0, -3.0, -4.0]));
+    test_eq!(
+        unsafe { simd_select(mask, a, b) },
+        Simd::from_array([1.0, -2.0, -3.0, 4.0])
+    );
+    // Casting pointer vectors keeps the addresses.
+    let bytes: [u8; 16] = black_box([0; 16]);
+    let base = &bytes as *const u8;
+    let byte_ptrs: Simd<*const u8, 4> = Simd::from_array([
+        base,
+        base.wrapping_add(4),
+        base.wrapping_add(8),
+        base.wrapping_add(12),
+    ]);
+    let word_ptrs: Simd<*const u32, 4> = unsafe { simd_cast_ptr(black_box(byte_ptrs)) };
+    test_eq!(
+        word_ptrs.to_array().map(|ptr| ptr as usize),
+        byte_ptrs.to_array().map(|ptr| ptr as usize)
+    );
+    // Shuffles of two distinct vectors index into the lanes of both: 0..4 are the lanes of `a`, 4..8 are the lanes of `b`.
+    let a: Simd<i32, 4> = black_box(Simd::from_array([0, 1, 2, 3]));
+    let b: Simd<i32, 4> = black_box(Simd::from_array([4, 5, 6, 7]));
+    let interleaved: Simd<i32, 8> = simd_swizzle!(a, b, [0, 4, 1, 5, 2, 6, 3, 7]);
+    test_eq!(interleaved, Simd::from_array([0, 4, 1, 5, 2, 6, 3, 7]));
+    // Lane values distinct from lane indices, so a wrong lane mapping can't go unnoticed.
+    let lo: Simd<i32, 4> = black_box(Simd::from_array([10, 11, 12, 13]));
+    let hi: Simd<i32, 4> = black_box(Simd::from_array([-20, -21, -22, -23]));
+    let interleaved: Simd<i32, 8> = simd_swizzle!(lo, hi, [0, 4, 1, 5, 2, 6, 3, 7]);
+    test_eq!(
+        interleaved.to_array(),
+        [10, -20, 11, -21, 12, -22, 13, -23]
+    );
+    let reversed: Simd<i32, 8> = simd_swizzle!(lo, hi, [7, 3, 6, 2, 5, 1, 4, 0]);
+    test_eq!(
+        reversed.to_array(),
+        [-23, 13, -22, 12, -21, 11, -20, 10]
+    );
+    // Shuffles of a single vector with a constant mask use `Vector128.Shuffle`.
+    let swapped: Simd<i32, 4> = simd_swizzle!(a, [3, 1, 2, 0]);
+    test_eq!(swapped, Simd::from_array([3, 1, 2, 0]));
+    let splat: Simd<i32, 4> = simd_swizzle!(b, [2, 2, 2, 2]);
+    test_eq!(splat, Simd::from_array([6, 6, 6, 6]));
+    // Shuffles can also produce vectors shorter than their inputs.
+    let a: Simd<f32, 4> = black_box(Simd::from_array([0.5, 1.5, 2.5, 3.5]));
+    let b: Simd<f32, 4> = black_box(Simd::from_array([4.5, 5.5, 6.5, 7.5]));
+    let narrowed: Simd<f32, 2> = simd_swizzle!(a, b, [7, 0]);
+    test_eq!(narrowed, Simd::from_array([7.5, 0.5]));
+    // Reductions work on vectors with lengths .NET has no vector types for, and use every lane.
+    let odd = black_box(I32x3([1, 20, 300]));
+    test_eq!(unsafe { simd_reduce_add_unordered::<_, i32>(odd) }, 321);
+    test_eq!(
+        unsafe { simd_reduce_add_ordered::<_, i32>(odd, 4000) },
+        4321
+    );
+    test_eq!(unsafe { simd_reduce_min::<_, i32>(odd) }, 1);
+    test_eq!(unsafe { simd_reduce_max::<_, i32>(odd) }, 300);
+    // Vectors .NET has no vector types for use the scalar lane loop.
+    let sum = unsafe { simd_add(odd, black_box(I32x3([4, 5, 6]))) };
+    test_eq!(sum.0, [5, 25, 306]);
+    // So do masks .NET has no vector types for.
+    let all_set = black_box(I32x3([-1, -1, -1]));
+    let one_set = black_box(I32x3([0, 0, -1]));
+    let none_set = black_box(I32x3([0, 0, 0]));
+    test!(unsafe { simd_reduce_all(all_set) });
+    test!(!unsafe { simd_reduce_all(one_set) });
+    test!(unsafe { simd_reduce_any(one_set) });
+    test!(!unsafe { simd_reduce_any(none_set) });
+    // Unops, comparisons and selects use lane loops for them too.
+    test_eq!(unsafe { simd_neg(odd) }.0, [-1, -20, -300]);
+    let roots = unsafe { simd_fsqrt(black_box(F32x3([4.0, 9.0, 16.0]))) };
+    test_eq!(roots.0, [2.0, 3.0, 4.0]);
+    let other = black_box(I32x3([1, 0, 300]));
+    test_eq!(unsafe { simd_eq::<_, I32x3>(odd, other) }.0, [-1, 0, -1]);
+    test_eq!(unsafe { simd_ne::<_, I32x3>(odd, other) }.0, [0, -1, 0]);
+    test_eq!(unsafe { simd_select(one_set, odd, sum) }.0, [5, 25, 300]);
+    let single = black_box(I32x1([-7]));
+    test_eq!(unsafe { simd_add(single, single) }.0, [-14]);
+    test_eq!(unsafe { simd_reduce_add_unordered::<_, i32>(single) }, -7);
+    test_eq!(unsafe { simd_reduce_max::<_, i32>(single) }, -7);
+    let wide: Simd<i32, 4> = black_box(Simd::from_array([5, -3, 2, 9]));
+    test_eq!(unsafe { simd_reduce_min::<_, i32>(wide) }, -3);
+    // Vectors can be loaded from, and stored to, memory as a whole.
+    let src = black_box(Aligned([1.0_f32, 2.0, 3.0, 4.0]));
+    let mut dst = Aligned([0.0_f32; 4]);
+    unsafe {
+        let loaded = *(src.0.as_ptr() as *const Simd<f32, 4>);
+        test_eq!(loaded, Simd::from_array([1.0, 2.0, 3.0, 4.0]));
+        *(dst.0.as_mut_ptr() as *mut Simd<f32, 4>) = loaded;
+    }
+    test_eq!(black_box(dst).0, [1.0, 2.0, 3.0, 4.0]);
+    // Vectors convert to and from arrays of their lanes.
+    let vec: Simd<i32, 4> = Simd::from_array(black_box([10, -20, 30, -40]));
+    let array: [i32; 4] = black_box(vec).to_array();
+    test_eq!(array[0], 10);
+    test_eq!(array[1], -20);
+    test_eq!(array[2], 30);
+    test_eq!(array[3], -40);
+    test_eq!(Simd::from_array(array), vec);
+    let vec = black_box(I32x4(black_box([5, 6, 7, 8])));
+    test_eq!(vec.0, [5, 6, 7, 8]);
+    test_eq!(vec.0[2], 7);
+    // The size and alignment of vectors match their Rust layout.
+    const F32X4_SIZE: usize = core::mem::size_of::<Simd<f32, 4>>();
+    const F32X4_ALIGN: usize = core::mem::align_of::<Simd<f32, 4>>();
+    const I32X3_SIZE: usize = core::mem::size_of::<I32x3>();
+    test_eq!(F32X4_SIZE, 16);
+    test_eq!(F32X4_ALIGN, 16);
+    test_eq!(black_box(core::mem::size_of::<Simd<f32, 4>>()), F32X4_SIZE);
+    test_eq!(
+        black_box(core::mem::align_of::<Simd<f32, 4>>()),
+        F32X4_ALIGN
+    );
+    test_eq!(
+        core::mem::size_of_val(&black_box(Simd::<f32, 4>::splat(1.0))),
+        F32X4_SIZE
+    );
+    test_eq!(black_box(core::mem::size_of::<I32x3>()), I32X3_SIZE);
+    test_eq!(core::mem::size_of_val(&black_box(odd)), I32X3_SIZE);
+    // Comparison masks feeding control flow.
+    let a: Simd<i32, 4> = black_box(Simd::from_array([1, 2, 3, 4]));
+    let same: Simd<i32, 4> = black_box(Simd::from_array([1, 2, 3, 4]));
+    let one_differs: Simd<i32, 4> = black_box(Simd::from_array([1, 2, 0, 4]));
+    let all_differ: Simd<i32, 4> = black_box(Simd::from_array([5, 6, 7, 8]));
+    test!(a.simd_eq(same).all());
+    test!(a.simd_eq(same).any());
+    test!(!a.simd_eq(one_differs).all());
+    test!(a.simd_eq(one_differs).any());
+    test!(!a.simd_eq(all_differ).all());
+    test!(!a.simd_eq(all_differ).any());
+    // Functions with `#[target_feature]`s can assume hardware support for wider vectors: `avx` covers 256 bit ones,
+    // so this add does not need the runtime check.
+    #[cfg(target_arch = "x86_64")]
+    test_eq!(
+        unsafe {
+            add_avx(
+                black_box(Simd::from_array([0.5, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5])),
+                Simd::splat(2.0),
+            )
+        },
+        Simd::from_array([2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5, 9.5])
+    );
+    // Vectors wider than the target features guarantee support for check for hardware support at runtime.
+    let wide: Simd<i32, 8> = black_box(Simd::from_array([0, 1, 2, 3, 4, 5, 6, 7]));
+    test_eq!(
+        unsafe { simd_add(wide, Simd::splat(100)) },
+        Simd::from_array([100, 101, 102, 103, 104, 105, 106, 107])
+    );
+    // Code generic over the lane count only reaches the backend once `N` is known.
+    test_eq!(
+        add_generic(black_box(Simd::from_array([1, 2])), Simd::splat(10)),
+        Simd::from_array([11, 12])
+    );
+    test_eq!(
+        add_generic(black_box(Simd::from_array([1, 2, 3, 4])), Simd::splat(10)),
+        Simd::from_array([11, 12, 13, 14])
+    );
+}
+fn add_generic<const N: usize>(a: Simd<i32, N>, b: Simd<i32, N>) -> Simd<i32, N>
+where
+    LaneCount<N>: SupportedLaneCount,
+{
+    unsafe { simd_add(a, b) }
+}
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn add_avx(a: Simd<f32, 8>, b: Simd<f32, 8>) -> Simd<f32, 8> {
+    simd_add(a, b)
 }
@@ -0,0 +1,32 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("../common.rs");
+extern crate core;
+
+use core::intrinsics::is_val_statically_known;
+
+const HALF: f64 = 0.5;
+const NULL: *const u8 = core::ptr::null();
+
+fn main() {
+    // Constants are known at compile time.
+    test!(is_val_statically_known(4_i32));
+    test!(is_val_statically_known(HALF));
+    test!(is_val_statically_known(1.5_f32));
+    test!(is_val_statically_known(true));
+    test!(is_val_statically_known(NULL));
+    // Values computed at runtime are not.
+    test!(!is_val_statically_known(black_box(4_i32)));
+    test!(!is_val_statically_known(black_box(HALF)));
+    test!(!is_val_statically_known(black_box(1.5_f32)));
+    test!(!is_val_statically_known(black_box(true)));
+    test!(!is_val_statically_known(black_box(NULL)));
+}
@@ -11,7 +11,7 @@
 include!("../common.rs");
 extern crate core;
 
-use core::intrinsics::transmute;
+use core::intrinsics::{transmute, transmute_unchecked};
 
 fn main() {
     let slice = unsafe { transmute::<&str, &[u8]>(".NET") };
@@ -25,6 +25,10 @@ fn main() {
         transmute::<*const (), fn() -> i32>(pointer)
     };
     test_eq!(function(), black_box(42));
+    // Function pointers survive a round trip trough `usize`.
+    let addr = unsafe { transmute::<fn() -> i32, usize>(black_box(forty_two as fn() -> i32)) };
+    let function = unsafe { transmute::<usize, fn() -> i32>(black_box(addr)) };
+    test_eq!(function(), black_box(42));
 
 
     let ptr_i32: &mut i32 = &mut 3;
@@ -33,4 +37,9 @@ fn main() {
     };
     let ptr_u32: &mut u32 = &mut 3;
     test_eq!(transmuted_to_u32, black_box(ptr_u32));
+    // `transmute_unchecked` reinterprets the value just like `transmute`.
+    let bits = unsafe { transmute_unchecked::<f32, u32>(black_box(1.0_f32)) };
+    test_eq!(bits, 0x3f80_0000_u32);
+    let pair = unsafe { transmute_unchecked::<u64, [u32; 2]>(black_box(0x0000_0002_0000_0001)) };
+    test_eq!(pair, [1, 2]);
 }
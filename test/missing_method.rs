@@ -0,0 +1,19 @@
+#![feature(
+    lang_items,
+    adt_const_params,
+    associated_type_defaults,
+    core_intrinsics,
+    start,
+    unsized_const_params
+)]
+#![allow(internal_features, incomplete_features, unused_variables, dead_code)]
+#![no_std]
+include!("common.rs");
+extern "C" {
+    // Not defined by any linked library, so the linker replaces it with a stub which throws.
+    fn rcl_missing_method_stub(arg: i32) -> i32;
+}
+fn main() {
+    let arg: i32 = black_box(7);
+    black_box(unsafe { rcl_missing_method_stub(arg) });
+}
@@ -0,0 +1,12 @@
+#![allow(dead_code)]
+// `open` fails with ENOENT here. The errno it sets must survive the trip back from native code, and be reported by
+// the resulting `io::Error`.
+const ENOENT: i32 = 2;
+fn main() {
+    let err = std::fs::File::open("/this/path/does/not/exist").unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(ENOENT));
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    // A second, independent failure should report its own errno too.
+    let err = std::fs::read_dir("/this/dir/does/not/exist").unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(ENOENT));
+}
@@ -0,0 +1,9 @@
+// Swapping values this big `localloc`s a temporary buffer of the same size, overflowing the stack, unless `LOCALLOC_CAP` is set.
+const SIZE: usize = 16 * 1024 * 1024;
+fn main() {
+    let mut a: Box<[u8; SIZE]> = vec![1_u8; SIZE].into_boxed_slice().try_into().unwrap();
+    let mut b: Box<[u8; SIZE]> = vec![2_u8; SIZE].into_boxed_slice().try_into().unwrap();
+    core::mem::swap(&mut *a, &mut *b);
+    assert!(a.iter().all(|byte| *byte == 2));
+    assert!(b.iter().all(|byte| *byte == 1));
+}
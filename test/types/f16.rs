@@ -9,6 +9,7 @@
 #![allow(internal_features, incomplete_features, unused_variables, dead_code)]
 #![no_std]
 include!("../common.rs");
+use core::intrinsics::{fabsf16, fmaf16, powif16, sqrtf16};
 #[inline(never)]
 #[no_mangle]
 
@@ -25,4 +26,15 @@ fn main() {
     test_eq!(black_box(one * one), one);
     // Check that division works
     test_eq!(black_box(two / one), two);
+    // Math intrinsics are emulated using f32.
+    let four = black_box(4_f16);
+    test_eq!(unsafe { sqrtf16(four) }, two);
+    test_eq!(unsafe { sqrtf16(black_box(2.25_f16)) }, black_box(1.5_f16));
+    test_eq!(unsafe { fmaf16(two, two, one) }, black_box(5_f16));
+    test_eq!(unsafe { fabsf16(black_box(-2_f16)) }, two);
+    // Small constant exponents are a chain of multiplications, everything else is computed in f32.
+    test_eq!(unsafe { powif16(two, 3) }, black_box(8_f16));
+    test_eq!(unsafe { powif16(black_box(-3_f16), 0) }, one);
+    test_eq!(unsafe { powif16(two, black_box(-2)) }, black_box(0.25_f16));
+    test_eq!(unsafe { powif16(two, 10) }, black_box(1024_f16));
 }